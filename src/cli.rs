@@ -36,6 +36,30 @@ pub struct Args {
     #[arg(long, value_enum, default_value_t = NonJsonMode::PrintAsIs)]
     pub non_json: NonJsonMode,
 
+    /// Look for a JSON object after a plain-text prefix (e.g. `[pod/web] {...}`)
+    /// on lines that are not JSON from the start. The prefix is shown by `{_prefix}`.
+    #[arg(long)]
+    pub find_json: bool,
+
+    /// Join JSON objects spread over several lines (pretty-printed or
+    /// concatenated output) into one record each.
+    #[arg(long)]
+    pub multiline: bool,
+
+    /// Append non-JSON lines that look like stack trace continuations
+    /// (indented, `at ...`, `Caused by:`) to the preceding record's stack trace.
+    #[arg(long)]
+    pub join_continuations: bool,
+
+    /// Parse plain-text lines with this regex. Named captures `timestamp`, `level`,
+    /// `logger`, `message` and `stack_trace` fill those roles; others become extra fields.
+    #[arg(long, value_name = "REGEX", conflicts_with = "line_format")]
+    pub line_regex: Option<String>,
+
+    /// Parse plain-text lines with a built-in format, such as web server access logs.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub line_format: Option<LinePreset>,
+
     /// Force a specific log schema instead of auto-detecting.
     #[arg(long, value_enum, default_value_t = SchemaChoice::Auto)]
     pub schema: SchemaChoice,
@@ -56,6 +80,49 @@ pub struct Args {
     #[arg(long)]
     pub min_level: Option<Level>,
 
+    /// Filter expression evaluated against each record,
+    /// e.g. `status >= 500 && logger ~ "com.acme.*" && !exists(user_id)`.
+    #[arg(long = "where", value_name = "EXPR")]
+    pub where_expr: Option<String>,
+
+    /// Only show records whose message (or a `--grep-fields` field) matches this regex.
+    /// Matches are highlighted in the output.
+    #[arg(long, value_name = "REGEX")]
+    pub grep: Option<String>,
+
+    /// Comma-separated list of fields searched by `--grep` (default: message).
+    #[arg(long, requires = "grep")]
+    pub grep_fields: Option<String>,
+
+    /// Make `--grep` case-insensitive.
+    #[arg(short = 'i', long, requires = "grep")]
+    pub ignore_case: bool,
+
+    /// Invert `--grep`: only show records that do not match.
+    #[arg(long, requires = "grep")]
+    pub invert: bool,
+
+    /// Show N records after each record that passes the filters.
+    #[arg(short = 'A', long, value_name = "N")]
+    pub after_context: Option<usize>,
+
+    /// Show N records before each record that passes the filters.
+    #[arg(short = 'B', long, value_name = "N")]
+    pub before_context: Option<usize>,
+
+    /// Show N records before and after each record that passes the filters.
+    #[arg(short = 'C', long, value_name = "N")]
+    pub context: Option<usize>,
+
+    /// Only show records at or after this time. Accepts absolute times
+    /// ("2024-01-15T10:00", "10:30") or relative ones ("15m", "2h ago").
+    #[arg(long, value_name = "TIME")]
+    pub since: Option<String>,
+
+    /// Only show records at or before this time. Accepts the same formats as `--since`.
+    #[arg(long, value_name = "TIME")]
+    pub until: Option<String>,
+
     /// Output records as raw JSON instead of formatted text.
     #[arg(long)]
     pub raw_json: bool,
@@ -76,11 +143,30 @@ pub struct Args {
     #[arg(long, default_value = "local")]
     pub tz: String,
 
-    /// Follow the last input file, waiting for new data (like tail -f).
-    /// When multiple files are given, preceding files are read to completion first.
+    /// Follow the input files, waiting for new data (like tail -f).
+    /// When multiple files are given, all are followed and each line is prefixed with its file name.
     #[arg(long)]
     pub follow: bool,
 
+    /// With --follow, start with the last N lines of each file instead of all
+    /// of it (0 shows only new lines).
+    #[arg(short = 'n', long, value_name = "N", requires = "follow")]
+    pub lines: Option<usize>,
+
+    /// With --follow, only show lines appended after startup (same as -n 0).
+    #[arg(long, requires = "follow", conflicts_with = "lines")]
+    pub from_end: bool,
+
+    /// With --follow, keep trying to open files that do not exist yet and keep
+    /// following files that are deleted and recreated.
+    #[arg(long, requires = "follow")]
+    pub retry: bool,
+
+    /// Merge all input files into a single stream ordered by timestamp.
+    /// Use {_file} in the format template to show each record's source file.
+    #[arg(long, conflicts_with = "follow")]
+    pub merge: bool,
+
     /// Write output to a file instead of stdout.
     #[arg(short = 'o', long)]
     pub output: Option<PathBuf>,
@@ -89,7 +175,8 @@ pub struct Args {
     #[arg(long, value_enum, exclusive = true)]
     pub completions: Option<Shell>,
 
-    /// Input file(s) to read. If omitted, reads from stdin.
+    /// Input file(s), directories or quoted glob patterns to read. If omitted, reads from stdin.
+    /// Directories and globs are read as rotation sets, oldest file first.
     #[arg()]
     pub files: Vec<PathBuf>,
 }
@@ -145,10 +232,35 @@ pub enum SchemaChoice {
     Logrus,
     /// Bunyan JSON format.
     Bunyan,
+    /// systemd journal entries from `journalctl -o json`.
+    Journald,
+    /// Google Cloud Logging structured logs and exported log entries.
+    Gcp,
+    /// Elastic Common Schema, as written by the ecs-logging libraries.
+    Ecs,
+    /// Serilog's compact JSON format (CLEF).
+    Clef,
+    /// Go's `log/slog` JSONHandler.
+    Slog,
+    /// Uber's zap JSON encoder.
+    Zap,
+    /// zerolog JSON format.
+    Zerolog,
     /// Generic fallback with common field name guessing.
     Generic,
 }
 
+/// Built-in formats for plain-text log lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LinePreset {
+    /// Apache Common Log Format.
+    Clf,
+    /// Apache Combined Log Format (CLF plus referer and user agent).
+    Combined,
+    /// nginx's default access log, optionally followed by X-Forwarded-For.
+    Nginx,
+}
+
 /// Shell for which to generate completion scripts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum Shell {
@@ -189,17 +301,36 @@ mod tests {
         assert!(args.omit_fields.is_none());
         assert_eq!(args.color, ColorMode::Auto);
         assert_eq!(args.non_json, NonJsonMode::PrintAsIs);
+        assert!(!args.find_json);
+        assert!(!args.multiline);
+        assert!(!args.join_continuations);
+        assert!(args.line_regex.is_none());
+        assert!(args.line_format.is_none());
         assert_eq!(args.schema, SchemaChoice::Auto);
         assert_eq!(args.logger_format, LoggerFormat::ShortDots);
         assert_eq!(args.logger_length, 30);
         assert_eq!(args.ts_format, TsFormat::Time);
         assert!(args.min_level.is_none());
+        assert!(args.where_expr.is_none());
+        assert!(args.grep.is_none());
+        assert!(args.grep_fields.is_none());
+        assert!(!args.ignore_case);
+        assert!(!args.invert);
+        assert!(args.after_context.is_none());
+        assert!(args.before_context.is_none());
+        assert!(args.context.is_none());
+        assert!(args.since.is_none());
+        assert!(args.until.is_none());
         assert!(!args.raw_json);
         assert!(!args.expanded);
         assert_eq!(args.key_color, CliColor::Magenta);
         assert_eq!(args.value_color, CliColor::Cyan);
         assert_eq!(args.tz, "local");
         assert!(!args.follow);
+        assert!(args.lines.is_none());
+        assert!(!args.from_end);
+        assert!(!args.retry);
+        assert!(!args.merge);
         assert!(args.output.is_none());
         assert!(args.completions.is_none());
         assert!(args.files.is_empty());
@@ -281,6 +412,27 @@ mod tests {
         let args = parse_args(&["jl", "--schema", "bunyan"]);
         assert_eq!(args.schema, SchemaChoice::Bunyan);
 
+        let args = parse_args(&["jl", "--schema", "journald"]);
+        assert_eq!(args.schema, SchemaChoice::Journald);
+
+        let args = parse_args(&["jl", "--schema", "gcp"]);
+        assert_eq!(args.schema, SchemaChoice::Gcp);
+
+        let args = parse_args(&["jl", "--schema", "ecs"]);
+        assert_eq!(args.schema, SchemaChoice::Ecs);
+
+        let args = parse_args(&["jl", "--schema", "clef"]);
+        assert_eq!(args.schema, SchemaChoice::Clef);
+
+        let args = parse_args(&["jl", "--schema", "slog"]);
+        assert_eq!(args.schema, SchemaChoice::Slog);
+
+        let args = parse_args(&["jl", "--schema", "zap"]);
+        assert_eq!(args.schema, SchemaChoice::Zap);
+
+        let args = parse_args(&["jl", "--schema", "zerolog"]);
+        assert_eq!(args.schema, SchemaChoice::Zerolog);
+
         let args = parse_args(&["jl", "--schema", "generic"]);
         assert_eq!(args.schema, SchemaChoice::Generic);
     }
@@ -297,12 +449,124 @@ mod tests {
         assert_eq!(args.min_level, Some(Level::Error));
     }
 
+    #[test]
+    fn where_expression() {
+        let args = parse_args(&["jl", "--where", "status >= 500 && exists(user_id)"]);
+        assert_eq!(
+            args.where_expr.as_deref(),
+            Some("status >= 500 && exists(user_id)")
+        );
+    }
+
+    #[test]
+    fn grep_options() {
+        let args = parse_args(&["jl", "--grep", "time.?out", "-i", "--invert"]);
+        assert_eq!(args.grep.as_deref(), Some("time.?out"));
+        assert!(args.ignore_case);
+        assert!(args.invert);
+
+        let args = parse_args(&["jl", "--grep", "db", "--grep-fields", "message,host"]);
+        assert_eq!(args.grep_fields.as_deref(), Some("message,host"));
+    }
+
+    #[test]
+    fn grep_modifiers_require_grep() {
+        assert!(Args::try_parse_from(["jl", "-i"]).is_err());
+        assert!(Args::try_parse_from(["jl", "--invert"]).is_err());
+        assert!(Args::try_parse_from(["jl", "--grep-fields", "host"]).is_err());
+    }
+
+    #[test]
+    fn context_options() {
+        let args = parse_args(&["jl", "-A", "3", "-B", "20"]);
+        assert_eq!(args.after_context, Some(3));
+        assert_eq!(args.before_context, Some(20));
+
+        let args = parse_args(&["jl", "-C", "5"]);
+        assert_eq!(args.context, Some(5));
+
+        let args = parse_args(&[
+            "jl",
+            "--after-context",
+            "1",
+            "--before-context",
+            "2",
+            "--context",
+            "3",
+        ]);
+        assert_eq!(args.after_context, Some(1));
+        assert_eq!(args.before_context, Some(2));
+        assert_eq!(args.context, Some(3));
+    }
+
+    #[test]
+    fn time_range() {
+        let args = parse_args(&["jl", "--since", "2h ago", "--until", "2024-01-15T10:00"]);
+        assert_eq!(args.since.as_deref(), Some("2h ago"));
+        assert_eq!(args.until.as_deref(), Some("2024-01-15T10:00"));
+    }
+
+    #[test]
+    fn follow_lines() {
+        let args = parse_args(&["jl", "--follow", "-n", "100", "app.log"]);
+        assert_eq!(args.lines, Some(100));
+        let args = parse_args(&["jl", "--follow", "--from-end", "app.log"]);
+        assert!(args.from_end);
+        assert!(Args::try_parse_from(["jl", "-n", "10", "app.log"]).is_err());
+        assert!(Args::try_parse_from(["jl", "--follow", "-n", "1", "--from-end", "a"]).is_err());
+    }
+
+    #[test]
+    fn retry_requires_follow() {
+        let args = parse_args(&["jl", "--follow", "--retry", "app.log"]);
+        assert!(args.retry);
+        assert!(Args::try_parse_from(["jl", "--retry", "app.log"]).is_err());
+    }
+
+    #[test]
+    fn merge_conflicts_with_follow() {
+        let args = parse_args(&["jl", "--merge", "a.log", "b.log"]);
+        assert!(args.merge);
+        assert!(Args::try_parse_from(["jl", "--merge", "--follow", "a.log"]).is_err());
+    }
+
+    #[test]
+    fn line_regex() {
+        let args = parse_args(&["jl", "--line-regex", r"(?P<level>\w+) (?P<message>.*)"]);
+        assert_eq!(
+            args.line_regex.as_deref(),
+            Some(r"(?P<level>\w+) (?P<message>.*)")
+        );
+    }
+
+    #[test]
+    fn line_format_presets() {
+        let args = parse_args(&["jl", "--line-format", "combined"]);
+        assert_eq!(args.line_format, Some(LinePreset::Combined));
+        let args = parse_args(&["jl", "--line-format", "nginx"]);
+        assert_eq!(args.line_format, Some(LinePreset::Nginx));
+        let result =
+            Args::try_parse_from(["jl", "--line-format", "clf", "--line-regex", "(?P<m>.*)"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn boolean_flags() {
-        let args = parse_args(&["jl", "--raw-json", "--expanded", "--follow"]);
+        let args = parse_args(&[
+            "jl",
+            "--raw-json",
+            "--expanded",
+            "--follow",
+            "--find-json",
+            "--multiline",
+            "--join-continuations",
+        ]);
         assert!(args.raw_json);
         assert!(args.expanded);
         assert!(args.follow);
+        assert!(args.find_json);
+        assert!(args.multiline);
+        assert!(args.join_continuations);
     }
 
     #[test]
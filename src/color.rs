@@ -87,6 +87,32 @@ impl ColorConfig {
         format!("{}", key.style(self.key_style))
     }
 
+    /// Style a `--grep` match so it stands out within the rendered line.
+    pub fn style_match(&self, text: &str) -> String {
+        if !self.enabled {
+            return text.to_string();
+        }
+        format!("{}", text.style(Style::new().black().on_yellow()))
+    }
+
+    /// Style a per-file prefix in `--follow` output. Files are told apart by
+    /// cycling through a fixed palette by their position on the command line.
+    pub fn style_file_prefix(&self, text: &str, index: usize) -> String {
+        if !self.enabled {
+            return text.to_string();
+        }
+        const PALETTE: [CliColor; 6] = [
+            CliColor::Cyan,
+            CliColor::Magenta,
+            CliColor::Blue,
+            CliColor::Yellow,
+            CliColor::Green,
+            CliColor::Red,
+        ];
+        let style = cli_color_to_style(PALETTE[index % PALETTE.len()]);
+        format!("{}", text.style(style))
+    }
+
     /// Style an extra field value using the configured value color.
     pub fn style_extra_value(&self, val: &str) -> String {
         if !self.enabled {
@@ -219,6 +245,39 @@ mod tests {
         assert!(styled.contains("server1"));
     }
 
+    #[test]
+    fn style_match_no_color_returns_plain() {
+        let config = ColorConfig::with_enabled(false);
+        assert_eq!(config.style_match("needle"), "needle");
+    }
+
+    #[test]
+    fn style_match_with_color_uses_yellow_background() {
+        let config = ColorConfig::with_enabled(true);
+        let styled = config.style_match("needle");
+        // Yellow background uses ANSI code 43
+        assert!(styled.contains("43"));
+        assert!(styled.contains("needle"));
+    }
+
+    #[test]
+    fn style_file_prefix_no_color_returns_plain() {
+        let config = ColorConfig::with_enabled(false);
+        assert_eq!(config.style_file_prefix("[a.log]", 3), "[a.log]");
+    }
+
+    #[test]
+    fn style_file_prefix_cycles_palette() {
+        let config = ColorConfig::with_enabled(true);
+        // First file is cyan (\x1b[36m), second magenta (\x1b[35m)
+        assert!(config.style_file_prefix("[a]", 0).contains("\x1b[36m"));
+        assert!(config.style_file_prefix("[b]", 1).contains("\x1b[35m"));
+        assert_eq!(
+            config.style_file_prefix("[g]", 6),
+            config.style_file_prefix("[g]", 0)
+        );
+    }
+
     #[test]
     fn style_extra_key_custom_color() {
         let config = ColorConfig::new(ColorMode::Always, CliColor::Cyan, CliColor::Green);
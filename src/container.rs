@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use chrono::DateTime;
+use serde_json::Value;
+
+use crate::cli::TsFormat;
+use crate::error::JlError;
+use crate::record::LogRecord;
+use crate::timestamp;
+
+/// Keys the Docker `json-file` log driver writes. `attrs` only appears when
+/// log labels or env options are configured.
+const ENVELOPE_KEYS: [&str; 4] = ["log", "stream", "time", "attrs"];
+
+/// A chunk of container output wrapped by the container runtime, carrying
+/// the stream it was written to and when it was received.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Envelope {
+    /// What the container wrote. A chunk that completes a line ends with a
+    /// newline; one continued by the next chunk does not.
+    pub log: String,
+    /// `stdout` or `stderr`.
+    pub stream: String,
+    /// When the runtime received the chunk.
+    pub time: String,
+}
+
+impl Envelope {
+    /// Recognize a line written by the Docker `json-file` log driver, as found
+    /// in `/var/lib/docker/containers/*/*-json.log`:
+    /// `{"log":"<line>\n","stream":"stdout","time":"2024-01-15T10:30:00.123Z"}`.
+    ///
+    /// Only objects made up of the envelope keys alone qualify, so ordinary
+    /// records that happen to carry a `log` field are left alone.
+    pub fn from_value(value: &Value) -> Option<Envelope> {
+        let obj = value.as_object()?;
+        if !obj.keys().all(|k| ENVELOPE_KEYS.contains(&k.as_str())) {
+            return None;
+        }
+        Some(Envelope {
+            log: obj.get("log")?.as_str()?.to_string(),
+            stream: obj.get("stream")?.as_str()?.to_string(),
+            time: obj.get("time")?.as_str()?.to_string(),
+        })
+    }
+
+    /// Recognize a line in the Kubernetes CRI format, as found under
+    /// `/var/log/pods`: `2024-01-15T10:30:00.123Z stdout F <line>`, where the
+    /// `F` tag marks a full line and `P` a partial one continued on the next.
+    pub fn from_cri(line: &str) -> Option<Envelope> {
+        if !line.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let (time, rest) = line.split_once(' ')?;
+        let (stream, rest) = rest.split_once(' ')?;
+        let (tag, content) = rest.split_once(' ').unwrap_or((rest, ""));
+        if !matches!(stream, "stdout" | "stderr") || DateTime::parse_from_rfc3339(time).is_err() {
+            return None;
+        }
+        // Tags are `:`-separated; only the first, the partial flag, is defined
+        let log = match tag.split(':').next()? {
+            "F" => format!("{content}\n"),
+            "P" => content.to_string(),
+            _ => return None,
+        };
+        Some(Envelope {
+            log,
+            stream: stream.to_string(),
+            time: time.to_string(),
+        })
+    }
+
+    /// Fill in what the inner record lacks from the envelope: the outer time
+    /// when the record has no timestamp field of its own, and `stream` as an
+    /// extra field unless the record already has one. A timestamp that is
+    /// present but cannot be parsed is kept as written.
+    pub fn apply_fallbacks(
+        &self,
+        record: &mut LogRecord,
+        tz: &str,
+        ts_format: TsFormat,
+    ) -> Result<(), JlError> {
+        if record.timestamp.is_none()
+            && let Some(datetime) = timestamp::parse_timestamp(&Value::String(self.time.clone()))
+        {
+            record.timestamp = Some(timestamp::format_timestamp(&datetime, tz, ts_format)?);
+            record.datetime = Some(datetime);
+        }
+        record
+            .extras
+            .entry("stream".to_string())
+            .or_insert_with(|| Value::String(self.stream.clone()));
+        Ok(())
+    }
+}
+
+/// Joins container lines that the runtime split into several envelopes.
+///
+/// Docker cuts lines longer than 16 KB into chunks, and CRI runtimes mark all
+/// but the last chunk of a long line as partial. Chunks are collected per
+/// stream, since stdout and stderr lines may interleave.
+#[derive(Debug, Default)]
+pub struct Reassembler {
+    partials: HashMap<String, Envelope>,
+}
+
+impl Reassembler {
+    /// Add an envelope, returning the complete line once its last chunk has
+    /// arrived, with the trailing newline removed. The returned envelope
+    /// carries the time of the line's first chunk.
+    pub fn push(&mut self, envelope: Envelope) -> Option<Envelope> {
+        let Some(tail) = envelope.log.strip_suffix('\n') else {
+            match self.partials.get_mut(&envelope.stream) {
+                Some(partial) => partial.log.push_str(&envelope.log),
+                None => {
+                    self.partials.insert(envelope.stream.clone(), envelope);
+                }
+            }
+            return None;
+        };
+        let tail = tail.strip_suffix('\r').unwrap_or(tail);
+        let line = match self.partials.remove(&envelope.stream) {
+            Some(mut partial) => {
+                partial.log.push_str(tail);
+                partial
+            }
+            None => Envelope {
+                log: tail.to_string(),
+                ..envelope
+            },
+        };
+        Some(line)
+    }
+
+    /// Take a line whose last chunk never arrived, once the input has ended.
+    pub fn flush(&mut self) -> Option<Envelope> {
+        let stream = self.partials.keys().min()?.clone();
+        self.partials.remove(&stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use serde_json::json;
+
+    fn envelope(log: &str, stream: &str, time: &str) -> Envelope {
+        Envelope {
+            log: log.to_string(),
+            stream: stream.to_string(),
+            time: time.to_string(),
+        }
+    }
+
+    #[test]
+    fn recognizes_envelope() {
+        let value = json!({"log": "hello\n", "stream": "stdout", "time": "2024-01-15T10:30:00Z"});
+        assert_eq!(
+            Envelope::from_value(&value),
+            Some(envelope("hello\n", "stdout", "2024-01-15T10:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn recognizes_envelope_with_attrs() {
+        let value = json!({
+            "log": "hello\n",
+            "stream": "stderr",
+            "attrs": {"tag": "web"},
+            "time": "2024-01-15T10:30:00Z"
+        });
+        assert!(Envelope::from_value(&value).is_some());
+    }
+
+    #[test]
+    fn ordinary_record_with_log_field_is_not_envelope() {
+        let value = json!({"log": "hello", "level": "INFO", "time": "2024-01-15T10:30:00Z"});
+        assert_eq!(Envelope::from_value(&value), None);
+        let value = json!({"log": "hello\n", "time": "2024-01-15T10:30:00Z"});
+        assert_eq!(Envelope::from_value(&value), None);
+    }
+
+    #[test]
+    fn recognizes_cri_lines() {
+        assert_eq!(
+            Envelope::from_cri(r#"2024-01-15T10:30:00.123456789Z stdout F {"msg":"hi"}"#),
+            Some(envelope(
+                "{\"msg\":\"hi\"}\n",
+                "stdout",
+                "2024-01-15T10:30:00.123456789Z"
+            ))
+        );
+        assert_eq!(
+            Envelope::from_cri("2024-01-15T10:30:00+02:00 stderr P part"),
+            Some(envelope("part", "stderr", "2024-01-15T10:30:00+02:00"))
+        );
+        assert_eq!(
+            Envelope::from_cri("2024-01-15T10:30:00Z stdout F"),
+            Some(envelope("\n", "stdout", "2024-01-15T10:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn rejects_non_cri_lines() {
+        assert_eq!(Envelope::from_cri("2024-01-15 10:30:00 INFO started"), None);
+        assert_eq!(
+            Envelope::from_cri("2024-01-15T10:30:00Z stdin F hello"),
+            None
+        );
+        assert_eq!(
+            Envelope::from_cri("2024-01-15T10:30:00Z stdout X hello"),
+            None
+        );
+        assert_eq!(Envelope::from_cri("not a timestamp stdout F hello"), None);
+        assert_eq!(Envelope::from_cri(r#"{"msg":"hi"}"#), None);
+    }
+
+    #[test]
+    fn joins_cri_partials() {
+        let mut reassembler = Reassembler::default();
+        let lines = [
+            "2024-01-15T10:30:00Z stdout P {\"msg\":",
+            "2024-01-15T10:30:01Z stdout P \"joined",
+            "2024-01-15T10:30:02Z stdout F \"}",
+        ];
+        let mut complete = None;
+        for line in lines {
+            complete = reassembler.push(Envelope::from_cri(line).unwrap());
+        }
+        assert_eq!(
+            complete,
+            Some(envelope(
+                "{\"msg\":\"joined\"}",
+                "stdout",
+                "2024-01-15T10:30:00Z"
+            ))
+        );
+    }
+
+    #[test]
+    fn complete_line_passes_through() {
+        let mut reassembler = Reassembler::default();
+        let line = reassembler
+            .push(envelope("hello\r\n", "stdout", "t1"))
+            .unwrap();
+        assert_eq!(line, envelope("hello", "stdout", "t1"));
+    }
+
+    #[test]
+    fn joins_split_line_per_stream() {
+        let mut reassembler = Reassembler::default();
+        assert_eq!(reassembler.push(envelope("{\"a\":", "stdout", "t1")), None);
+        let err = reassembler
+            .push(envelope("oops\n", "stderr", "t2"))
+            .unwrap();
+        assert_eq!(err.log, "oops");
+        assert_eq!(reassembler.push(envelope("\"b", "stdout", "t3")), None);
+        let line = reassembler
+            .push(envelope("c\"}\n", "stdout", "t4"))
+            .unwrap();
+        assert_eq!(line, envelope("{\"a\":\"bc\"}", "stdout", "t1"));
+        assert_eq!(reassembler.flush(), None);
+    }
+
+    #[test]
+    fn flush_returns_unterminated_line() {
+        let mut reassembler = Reassembler::default();
+        assert_eq!(reassembler.push(envelope("cut", "stdout", "t1")), None);
+        assert_eq!(reassembler.flush(), Some(envelope("cut", "stdout", "t1")));
+        assert_eq!(reassembler.flush(), None);
+    }
+
+    #[test]
+    fn fallbacks_fill_missing_time_and_stream() {
+        let mapping = Schema::Generic.field_mapping();
+        let value = json!({"level": "INFO", "msg": "hi"});
+        let mut record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        envelope("", "stderr", "2024-01-15T10:30:00.5Z")
+            .apply_fallbacks(&mut record, "utc", TsFormat::Full)
+            .unwrap();
+        assert_eq!(record.timestamp.as_deref(), Some("2024-01-15T10:30:00.500"));
+        assert!(record.datetime.is_some());
+        assert_eq!(record.extras.get("stream"), Some(&json!("stderr")));
+    }
+
+    #[test]
+    fn fallbacks_keep_inner_values() {
+        let mapping = Schema::Generic.field_mapping();
+        let value = json!({"time": "2024-01-15T09:00:00Z", "msg": "hi", "stream": "events"});
+        let mut record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        envelope("", "stdout", "2024-01-15T10:30:00Z")
+            .apply_fallbacks(&mut record, "utc", TsFormat::Full)
+            .unwrap();
+        assert!(record.timestamp.unwrap().starts_with("2024-01-15T09:00:00"));
+        assert_eq!(record.extras.get("stream"), Some(&json!("events")));
+    }
+
+    #[test]
+    fn fallbacks_keep_unparseable_inner_time() {
+        let mapping = Schema::Generic.field_mapping();
+        let value = json!({"time": "yesterday", "msg": "hi"});
+        let mut record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        envelope("", "stdout", "2024-01-15T10:30:00Z")
+            .apply_fallbacks(&mut record, "utc", TsFormat::Full)
+            .unwrap();
+        assert_eq!(record.timestamp.as_deref(), Some("yesterday"));
+        assert!(record.datetime.is_none());
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+
+use crate::error::JlError;
+use crate::output::OutputSink;
+
+/// Separator written between non-adjacent groups of context output.
+pub const GROUP_SEPARATOR: &str = "--";
+
+/// Keeps the records around each filter hit, like `grep -A/-B/-C`.
+///
+/// Every rendered line is reported as either a hit (passed the filters) or a
+/// miss (filtered out). Misses are held in a ring buffer of `before` lines so
+/// they can be emitted ahead of the next hit, and up to `after` misses
+/// following a hit are emitted directly. Overlapping windows merge naturally
+/// since each line is emitted at most once; a `--` separator is written
+/// whenever a gap appears between emitted lines.
+pub struct ContextBuffer {
+    before: usize,
+    after: usize,
+    pending: VecDeque<(u64, String)>,
+    after_remaining: usize,
+    next_index: u64,
+    last_emitted: Option<u64>,
+}
+
+impl ContextBuffer {
+    /// Create a buffer keeping `before` lines ahead of and `after` lines behind each hit.
+    pub fn new(before: usize, after: usize) -> Self {
+        Self {
+            before,
+            after,
+            pending: VecDeque::with_capacity(before),
+            after_remaining: 0,
+            next_index: 0,
+            last_emitted: None,
+        }
+    }
+
+    /// Whether any context lines were requested. When disabled, misses are
+    /// dropped and callers may skip rendering them altogether.
+    pub fn is_enabled(&self) -> bool {
+        self.before > 0 || self.after > 0
+    }
+
+    /// Record a line that passed the filters, flushing buffered context first.
+    pub fn hit(&mut self, line: String, output: &mut dyn OutputSink) -> Result<(), JlError> {
+        let index = self.advance();
+        while let Some((i, pending)) = self.pending.pop_front() {
+            self.emit(i, &pending, output)?;
+        }
+        self.emit(index, &line, output)?;
+        self.after_remaining = self.after;
+        Ok(())
+    }
+
+    /// Record a line that was filtered out; it is only written if it falls
+    /// within the context window of a hit.
+    pub fn miss(&mut self, line: String, output: &mut dyn OutputSink) -> Result<(), JlError> {
+        let index = self.advance();
+        if self.after_remaining > 0 {
+            self.after_remaining -= 1;
+            return self.emit(index, &line, output);
+        }
+        if self.before > 0 {
+            if self.pending.len() == self.before {
+                self.pending.pop_front();
+            }
+            self.pending.push_back((index, line));
+        }
+        Ok(())
+    }
+
+    fn advance(&mut self) -> u64 {
+        let index = self.next_index;
+        self.next_index += 1;
+        index
+    }
+
+    fn emit(&mut self, index: u64, line: &str, output: &mut dyn OutputSink) -> Result<(), JlError> {
+        if self.is_enabled()
+            && let Some(last) = self.last_emitted
+            && index > last + 1
+        {
+            output.write_line(GROUP_SEPARATOR)?;
+        }
+        output.write_line(line)?;
+        self.last_emitted = Some(index);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct VecSink(Vec<String>);
+
+    impl OutputSink for VecSink {
+        fn write_line(&mut self, line: &str) -> Result<(), JlError> {
+            self.0.push(line.to_string());
+            Ok(())
+        }
+    }
+
+    /// Feed a sequence of lines where uppercase names are hits and lowercase are misses.
+    fn run(before: usize, after: usize, lines: &[&str]) -> Vec<String> {
+        let mut buf = ContextBuffer::new(before, after);
+        let mut sink = VecSink::default();
+        for line in lines {
+            if line.chars().all(|c| c.is_ascii_uppercase()) {
+                buf.hit(line.to_string(), &mut sink).unwrap();
+            } else {
+                buf.miss(line.to_string(), &mut sink).unwrap();
+            }
+        }
+        sink.0
+    }
+
+    #[test]
+    fn disabled_drops_misses() {
+        let out = run(0, 0, &["a", "B", "c", "D"]);
+        assert_eq!(out, vec!["B", "D"]);
+    }
+
+    #[test]
+    fn before_context() {
+        let out = run(2, 0, &["a", "b", "c", "D", "e"]);
+        assert_eq!(out, vec!["b", "c", "D"]);
+    }
+
+    #[test]
+    fn after_context() {
+        let out = run(0, 2, &["a", "B", "c", "d", "e"]);
+        assert_eq!(out, vec!["B", "c", "d"]);
+    }
+
+    #[test]
+    fn separator_between_groups() {
+        let out = run(1, 1, &["a", "B", "c", "d", "e", "F", "g"]);
+        assert_eq!(out, vec!["a", "B", "c", "--", "e", "F", "g"]);
+    }
+
+    #[test]
+    fn overlapping_windows_merge() {
+        let out = run(2, 2, &["a", "B", "c", "d", "E", "f"]);
+        assert_eq!(out, vec!["a", "B", "c", "d", "E", "f"]);
+    }
+
+    #[test]
+    fn adjacent_hits_have_no_separator() {
+        let out = run(1, 0, &["A", "B", "c", "d", "E"]);
+        assert_eq!(out, vec!["A", "B", "--", "d", "E"]);
+    }
+
+    #[test]
+    fn no_hits_no_output() {
+        let out = run(3, 3, &["a", "b", "c"]);
+        assert!(out.is_empty());
+    }
+}
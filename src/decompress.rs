@@ -0,0 +1,124 @@
+use std::io::{self, BufRead, BufReader, Read};
+
+/// Compression formats recognized by their magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Identify the compression format from the first bytes of a stream.
+    pub fn from_magic(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Compression::Gzip)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Compression::Zstd)
+        } else if bytes.starts_with(b"BZh") {
+            Some(Compression::Bzip2)
+        } else {
+            None
+        }
+    }
+
+    /// Peek at the first bytes of a buffered reader, without consuming them,
+    /// to identify its compression format.
+    pub fn detect(reader: &mut impl BufRead) -> io::Result<Option<Self>> {
+        Ok(Self::from_magic(reader.fill_buf()?))
+    }
+
+    /// Wrap a reader positioned at the start of a compressed stream in a
+    /// streaming decoder. Concatenated gzip members, zstd frames and bzip2
+    /// streams are decoded one after another, as `zcat` does.
+    pub fn decoder<R: BufRead + 'static>(self, reader: R) -> io::Result<Box<dyn BufRead>> {
+        let decoder: Box<dyn Read> = match self {
+            Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+            Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(reader)?),
+            Compression::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(reader)),
+        };
+        Ok(Box::new(BufReader::new(decoder)))
+    }
+}
+
+/// Decode compressed input transparently; plain input is returned unchanged.
+pub fn maybe_decompress<R: BufRead + 'static>(mut reader: R) -> io::Result<Box<dyn BufRead>> {
+    match Compression::detect(&mut reader)? {
+        Some(compression) => compression.decoder(reader),
+        None => Ok(Box::new(reader)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    const TEXT: &str = "{\"level\":\"INFO\",\"message\":\"one\"}\nplain two\n";
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    fn bzip2(data: &[u8]) -> Vec<u8> {
+        let mut enc = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    fn decode(data: Vec<u8>) -> String {
+        let mut out = String::new();
+        maybe_decompress(Cursor::new(data))
+            .unwrap()
+            .read_to_string(&mut out)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn detects_magic_bytes() {
+        assert_eq!(
+            Compression::from_magic(&gzip(b"x")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_magic(&zstd::encode_all(&b"x"[..], 0).unwrap()),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(
+            Compression::from_magic(&bzip2(b"x")),
+            Some(Compression::Bzip2)
+        );
+        assert_eq!(Compression::from_magic(b"{\"level\":\"INFO\"}"), None);
+        assert_eq!(Compression::from_magic(b""), None);
+    }
+
+    #[test]
+    fn plain_input_unchanged() {
+        assert_eq!(decode(TEXT.as_bytes().to_vec()), TEXT);
+    }
+
+    #[test]
+    fn decodes_gzip() {
+        assert_eq!(decode(gzip(TEXT.as_bytes())), TEXT);
+    }
+
+    #[test]
+    fn decodes_concatenated_gzip_members() {
+        let mut data = gzip(b"first\n");
+        data.extend(gzip(b"second\n"));
+        assert_eq!(decode(data), "first\nsecond\n");
+    }
+
+    #[test]
+    fn decodes_zstd() {
+        assert_eq!(decode(zstd::encode_all(TEXT.as_bytes(), 0).unwrap()), TEXT);
+    }
+
+    #[test]
+    fn decodes_bzip2() {
+        assert_eq!(decode(bzip2(TEXT.as_bytes())), TEXT);
+    }
+}
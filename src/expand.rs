@@ -0,0 +1,318 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::JlError;
+
+/// Extensions of compressed archives, stripped before reading a file's
+/// rotation suffix.
+const COMPRESSED_EXTENSIONS: [&str; 4] = ["gz", "zst", "zstd", "bz2"];
+
+/// Extensions of the files a directory input expands to, besides rotated
+/// archives.
+const LOG_EXTENSIONS: [&str; 4] = ["log", "json", "jsonl", "ndjson"];
+
+/// Where a file sits in its rotation set. The derived ordering sorts the
+/// oldest file first and the live file last.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Generation {
+    /// `app.log.gz`: a compressed archive without a rotation suffix, whose
+    /// age is unknown.
+    Archived,
+    /// `app.log-20240115`: date-stamped archives, ordered by their date.
+    Dated(String),
+    /// `app.log.3`: numbered archives, where larger numbers are older.
+    Numbered(Reverse<u64>),
+    /// The file currently being written, `app.log`.
+    Live,
+}
+
+/// Expand directory and glob inputs into the files they contain.
+///
+/// A directory expands to the log files directly inside it: `.log`, `.json`,
+/// `.jsonl` and `.ndjson` files and their rotations, skipping hidden files
+/// and anything else, such as `.pid` or `.lock` files. A path that does not
+/// exist but contains glob metacharacters (`*`, `?`, `[`) expands to the
+/// files it matches, whatever their names. Each expansion is ordered as a
+/// rotation set, oldest first: `app.log.3.gz`, `app.log.2`, `app.log.1`,
+/// `app.log`. Plain file paths are kept as given, in command-line order.
+///
+/// With `live_only`, expanded archives are dropped and only the live file of
+/// each rotation set is kept, which is what `--follow` wants. Compressed files
+/// are never live. An expansion
+/// that yields no files is an error.
+pub fn expand_inputs(paths: &[PathBuf], live_only: bool) -> Result<Vec<PathBuf>, JlError> {
+    let mut files = Vec::new();
+    for path in paths {
+        let mut expanded = if path.is_dir() {
+            list_dir(path)?
+        } else if let Some(pattern) = glob_pattern(path) {
+            expand_glob(pattern)?
+        } else {
+            files.push(path.clone());
+            continue;
+        };
+        if live_only {
+            expanded.retain(|p| rotation_key(p).1 == Generation::Live);
+        }
+        if expanded.is_empty() {
+            let path_display = path.display();
+            return Err(JlError::Parse(format!(
+                "no log files found for {path_display}"
+            )));
+        }
+        sort_rotation_set(&mut expanded);
+        files.extend(expanded);
+    }
+    Ok(files)
+}
+
+/// The glob pattern in `path`, if it is one: a path that does not exist as a
+/// file and contains glob metacharacters.
+fn glob_pattern(path: &Path) -> Option<&str> {
+    let s = path.to_str()?;
+    let is_pattern = s.contains(['*', '?', '[']);
+    (is_pattern && !path.exists()).then_some(s)
+}
+
+fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>, JlError> {
+    let paths = glob::glob(pattern)
+        .map_err(|e| JlError::Parse(format!("invalid glob {pattern:?}: {e}")))?;
+    Ok(paths
+        .filter_map(Result::ok)
+        .filter(|p| p.is_file())
+        .collect())
+}
+
+fn list_dir(dir: &Path) -> Result<Vec<PathBuf>, JlError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type()?.is_file() && is_log_name(&entry.path()) {
+            files.push(entry.path());
+        }
+    }
+    Ok(files)
+}
+
+/// Whether a file looks like a log: `app.log` or a JSON lines file such as
+/// `app.jsonl`, a compressed copy of one, or a numbered or dated rotation of
+/// any file (`syslog.1`, `messages-20240115.gz`).
+fn is_log_name(path: &Path) -> bool {
+    let (base, generation) = rotation_key(path);
+    let is_log = || {
+        base.rsplit_once('.')
+            .is_some_and(|(_, ext)| LOG_EXTENSIONS.contains(&ext))
+    };
+    match generation {
+        Generation::Numbered(_) | Generation::Dated(_) => true,
+        Generation::Archived | Generation::Live => is_log(),
+    }
+}
+
+/// Sort files so that each rotation set is contiguous and oldest-first.
+/// Sets are ordered by their base name.
+fn sort_rotation_set(files: &mut [PathBuf]) {
+    files.sort_by_cached_key(|p| {
+        let (base, generation) = rotation_key(p);
+        (base, generation, p.clone())
+    });
+}
+
+/// Split a file name into its rotation set's base name and its generation,
+/// e.g. `app.log.2.gz` into `app.log` and `Numbered(2)`.
+fn rotation_key(path: &Path) -> (String, Generation) {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (stem, compressed) = match name.rsplit_once('.') {
+        Some((stem, ext)) if COMPRESSED_EXTENSIONS.contains(&ext) => (stem, true),
+        _ => (name.as_str(), false),
+    };
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if let Some((base, n)) = stem.rsplit_once('.')
+        && is_digits(n)
+        && let Ok(n) = n.parse()
+    {
+        return (base.to_string(), Generation::Numbered(Reverse(n)));
+    }
+    if let Some((base, date)) = stem.rsplit_once('-')
+        && date.len() >= 8
+        && is_digits(date)
+    {
+        return (base.to_string(), Generation::Dated(date.to_string()));
+    }
+    if compressed {
+        return (stem.to_string(), Generation::Archived);
+    }
+    (stem.to_string(), Generation::Live)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(paths: &[PathBuf]) -> Vec<String> {
+        paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn touch_all(dir: &Path, files: &[&str]) {
+        for f in files {
+            fs::write(dir.join(f), "").unwrap();
+        }
+    }
+
+    #[test]
+    fn rotation_keys() {
+        assert_eq!(
+            rotation_key(Path::new("/var/log/app.log")),
+            ("app.log".to_string(), Generation::Live)
+        );
+        assert_eq!(
+            rotation_key(Path::new("app.log.3.gz")),
+            ("app.log".to_string(), Generation::Numbered(Reverse(3)))
+        );
+        assert_eq!(
+            rotation_key(Path::new("app.log-20240115.zst")),
+            (
+                "app.log".to_string(),
+                Generation::Dated("20240115".to_string())
+            )
+        );
+        assert_eq!(
+            rotation_key(Path::new("app.log.gz")),
+            ("app.log".to_string(), Generation::Archived)
+        );
+    }
+
+    #[test]
+    fn sorts_numbered_rotation_oldest_first() {
+        let mut files: Vec<PathBuf> = [
+            "app.log",
+            "app.log.1",
+            "app.log.10.gz",
+            "app.log.2",
+            "app.log.3.gz",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        sort_rotation_set(&mut files);
+        assert_eq!(
+            names(&files),
+            [
+                "app.log.10.gz",
+                "app.log.3.gz",
+                "app.log.2",
+                "app.log.1",
+                "app.log"
+            ]
+        );
+    }
+
+    #[test]
+    fn sorts_dated_rotation_oldest_first() {
+        let mut files: Vec<PathBuf> = ["app.log", "app.log-20240116.gz", "app.log-20240115.gz"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        sort_rotation_set(&mut files);
+        assert_eq!(
+            names(&files),
+            ["app.log-20240115.gz", "app.log-20240116.gz", "app.log"]
+        );
+    }
+
+    #[test]
+    fn directory_expands_to_sorted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        touch_all(
+            dir.path(),
+            &[
+                "app.log",
+                "app.log.1",
+                "app.log.2",
+                "app.log.3.gz",
+                "db.log",
+                "events.jsonl",
+                "syslog.1",
+                ".hidden",
+                "app.pid",
+                "app.lock",
+                "backup.tar.gz",
+                "README",
+            ],
+        );
+        fs::create_dir(dir.path().join("subdir")).unwrap();
+
+        let files = expand_inputs(&[dir.path().to_path_buf()], false).unwrap();
+        assert_eq!(
+            names(&files),
+            [
+                "app.log.3.gz",
+                "app.log.2",
+                "app.log.1",
+                "app.log",
+                "db.log",
+                "events.jsonl",
+                "syslog.1"
+            ]
+        );
+    }
+
+    #[test]
+    fn glob_expands_to_sorted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        touch_all(
+            dir.path(),
+            &["app.log", "app.log.1", "app.log.2.gz", "other.txt"],
+        );
+
+        let pattern = dir.path().join("app.log*");
+        let files = expand_inputs(&[pattern], false).unwrap();
+        assert_eq!(names(&files), ["app.log.2.gz", "app.log.1", "app.log"]);
+    }
+
+    #[test]
+    fn glob_without_matches_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let pattern = dir.path().join("*.log");
+        let err = expand_inputs(&[pattern], false).unwrap_err();
+        assert!(format!("{err}").contains("no log files found"));
+    }
+
+    #[test]
+    fn empty_directory_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(expand_inputs(&[dir.path().to_path_buf()], false).is_err());
+    }
+
+    #[test]
+    fn plain_paths_kept_in_order() {
+        let paths = vec![PathBuf::from("b.log"), PathBuf::from("a.log")];
+        assert_eq!(expand_inputs(&paths, false).unwrap(), paths);
+    }
+
+    #[test]
+    fn live_only_drops_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        touch_all(
+            dir.path(),
+            &[
+                "app.log",
+                "app.log.1",
+                "db.log",
+                "db.log-20240115.gz",
+                "web.log.gz",
+            ],
+        );
+
+        let files = expand_inputs(&[dir.path().to_path_buf()], true).unwrap();
+        assert_eq!(names(&files), ["app.log", "db.log"]);
+    }
+}
@@ -0,0 +1,580 @@
+use std::cmp::Ordering;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::error::JlError;
+use crate::level::Level;
+use crate::record::LogRecord;
+
+/// A compiled `--where` filter expression.
+///
+/// Grammar (lowest to highest precedence):
+///
+/// ```text
+/// expr    := or
+/// or      := and ( "||" and )*
+/// and     := unary ( "&&" unary )*
+/// unary   := "!" unary | primary
+/// primary := "(" expr ")"
+///          | "exists" "(" field ")"
+///          | operand ( cmp_op operand | ("~" | "!~") string )?
+/// cmp_op  := "==" | "!=" | "<" | "<=" | ">" | ">="
+/// operand := field | string | number | "true" | "false" | "null"
+/// ```
+///
+/// Fields are resolved with `LogRecord::lookup`, so canonical names, extras and
+/// dot-separated paths into nested objects all work. A bare field is true when
+/// it is present and not `false`, `null` or an empty string.
+#[derive(Debug)]
+pub struct Filter {
+    expr: Expr,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CmpOp, Operand),
+    Match(Operand, Regex),
+    Exists(String),
+    Truthy(Operand),
+}
+
+#[derive(Debug)]
+enum Operand {
+    Field(String),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Cmp(CmpOp),
+    Match,
+    NotMatch,
+}
+
+impl Filter {
+    /// Compile a filter expression, returning a parse error on invalid syntax.
+    pub fn compile(source: &str) -> Result<Filter, JlError> {
+        let tokens = tokenize(source).map_err(|e| invalid(source, &e))?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or().map_err(|e| invalid(source, &e))?;
+        if let Some(tok) = parser.peek() {
+            return Err(invalid(source, &format!("unexpected token {tok:?}")));
+        }
+        Ok(Filter { expr })
+    }
+
+    /// Evaluate the expression against a record.
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        eval(&self.expr, record)
+    }
+}
+
+fn invalid(source: &str, reason: &str) -> JlError {
+    JlError::Parse(format!("invalid --where expression {source:?}: {reason}"))
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '&' | '|' => {
+                chars.next();
+                if chars.next() != Some(ch) {
+                    return Err(format!("expected '{ch}{ch}'"));
+                }
+                tokens.push(if ch == '&' { Token::And } else { Token::Or });
+            }
+            '=' => {
+                chars.next();
+                if chars.next() != Some('=') {
+                    return Err("expected '=='".to_string());
+                }
+                tokens.push(Token::Cmp(CmpOp::Eq));
+            }
+            '!' => {
+                chars.next();
+                match chars.peek() {
+                    Some('=') => {
+                        chars.next();
+                        tokens.push(Token::Cmp(CmpOp::Ne));
+                    }
+                    Some('~') => {
+                        chars.next();
+                        tokens.push(Token::NotMatch);
+                    }
+                    _ => tokens.push(Token::Not),
+                }
+            }
+            '<' | '>' => {
+                chars.next();
+                let or_equal = chars.peek() == Some(&'=');
+                if or_equal {
+                    chars.next();
+                }
+                tokens.push(Token::Cmp(match (ch, or_equal) {
+                    ('<', false) => CmpOp::Lt,
+                    ('<', true) => CmpOp::Le,
+                    (_, false) => CmpOp::Gt,
+                    (_, true) => CmpOp::Ge,
+                }));
+            }
+            '~' => {
+                chars.next();
+                tokens.push(Token::Match);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err("unterminated string".to_string()),
+                        Some(c) if c == ch => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(c) => s.push(c),
+                            None => return Err("unterminated string".to_string()),
+                        },
+                        Some(c) => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut s = String::new();
+                s.push(c);
+                chars.next();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' {
+                        s.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let n = s
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid number {s:?}"))?;
+                tokens.push(Token::Num(n));
+            }
+            c if is_ident_start(c) => {
+                let mut s = String::new();
+                while let Some(&c) = chars.peek() {
+                    if is_ident_continue(c) {
+                        s.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(s));
+            }
+            c => return Err(format!("unexpected character {c:?}")),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '@' || c == '$'
+}
+
+fn is_ident_continue(c: char) -> bool {
+    is_ident_start(c) || c.is_ascii_digit() || c == '.' || c == '-'
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(tok) if tok == expected => Ok(()),
+            Some(tok) => Err(format!("expected {expected:?}, found {tok:?}")),
+            None => Err(format!("expected {expected:?}, found end of input")),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::LParen) => {
+                self.next();
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                return Ok(expr);
+            }
+            Some(Token::Ident(name))
+                if name == "exists" && self.tokens.get(self.pos + 1) == Some(&Token::LParen) =>
+            {
+                self.pos += 2;
+                let field = match self.next() {
+                    Some(Token::Ident(field)) => field,
+                    Some(Token::Str(field)) => field,
+                    _ => return Err("exists() expects a field name".to_string()),
+                };
+                self.expect(Token::RParen)?;
+                return Ok(Expr::Exists(field));
+            }
+            _ => {}
+        }
+
+        let lhs = self.parse_operand()?;
+        match self.peek() {
+            Some(Token::Cmp(op)) => {
+                let op = *op;
+                self.next();
+                let rhs = self.parse_operand()?;
+                Ok(Expr::Compare(lhs, op, rhs))
+            }
+            Some(Token::Match) | Some(Token::NotMatch) => {
+                let negated = self.next() == Some(Token::NotMatch);
+                let pattern = match self.next() {
+                    Some(Token::Str(p)) => p,
+                    _ => return Err("'~' expects a quoted pattern".to_string()),
+                };
+                let regex = Regex::new(&pattern).map_err(|e| format!("invalid pattern: {e}"))?;
+                let expr = Expr::Match(lhs, regex);
+                Ok(if negated {
+                    Expr::Not(Box::new(expr))
+                } else {
+                    expr
+                })
+            }
+            _ => Ok(Expr::Truthy(lhs)),
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(match name.as_str() {
+                "true" => Operand::Literal(Value::Bool(true)),
+                "false" => Operand::Literal(Value::Bool(false)),
+                "null" => Operand::Literal(Value::Null),
+                _ => Operand::Field(name),
+            }),
+            Some(Token::Str(s)) => Ok(Operand::Literal(Value::String(s))),
+            Some(Token::Num(n)) => Ok(Operand::Literal(
+                serde_json::Number::from_f64(n)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null),
+            )),
+            Some(tok) => Err(format!("expected a field or value, found {tok:?}")),
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+}
+
+fn eval(expr: &Expr, record: &LogRecord) -> bool {
+    match expr {
+        Expr::Or(lhs, rhs) => eval(lhs, record) || eval(rhs, record),
+        Expr::And(lhs, rhs) => eval(lhs, record) && eval(rhs, record),
+        Expr::Not(inner) => !eval(inner, record),
+        Expr::Compare(lhs, op, rhs) => compare(lhs, *op, rhs, record),
+        Expr::Match(operand, regex) => match resolve(operand, record) {
+            Some(val) => regex.is_match(&display(&val)),
+            None => false,
+        },
+        Expr::Exists(field) => record.lookup(field).is_some(),
+        Expr::Truthy(operand) => match resolve(operand, record) {
+            Some(Value::Null) | Some(Value::Bool(false)) | None => false,
+            Some(Value::String(s)) => !s.is_empty(),
+            Some(_) => true,
+        },
+    }
+}
+
+fn resolve(operand: &Operand, record: &LogRecord) -> Option<Value> {
+    match operand {
+        Operand::Field(name) => record.lookup(name),
+        Operand::Literal(val) => Some(val.clone()),
+    }
+}
+
+/// Compare two operands. Missing fields behave like `null`: they are only
+/// equal to `null`, and never satisfy an ordering comparison.
+fn compare(lhs: &Operand, op: CmpOp, rhs: &Operand, record: &LogRecord) -> bool {
+    let l = resolve(lhs, record).unwrap_or(Value::Null);
+    let r = resolve(rhs, record).unwrap_or(Value::Null);
+
+    let ordering = if l.is_null() || r.is_null() {
+        if l.is_null() && r.is_null() {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    } else if let Some(ord) = compare_levels(lhs, &l, rhs, &r) {
+        Some(ord)
+    } else if let (Some(a), Some(b)) = (as_number(&l), as_number(&r)) {
+        a.partial_cmp(&b)
+    } else {
+        Some(display(&l).cmp(&display(&r)))
+    };
+
+    match (op, ordering) {
+        (CmpOp::Eq, ord) => ord == Some(Ordering::Equal),
+        (CmpOp::Ne, ord) => ord != Some(Ordering::Equal),
+        (_, None) => false,
+        (CmpOp::Lt, Some(ord)) => ord == Ordering::Less,
+        (CmpOp::Le, Some(ord)) => ord != Ordering::Greater,
+        (CmpOp::Gt, Some(ord)) => ord == Ordering::Greater,
+        (CmpOp::Ge, Some(ord)) => ord != Ordering::Less,
+    }
+}
+
+/// When one side is the canonical `level` field, compare by severity so that
+/// `level >= "warn"` includes ERROR and FATAL.
+fn compare_levels(lhs: &Operand, l: &Value, rhs: &Operand, r: &Value) -> Option<Ordering> {
+    let is_level = |op: &Operand| matches!(op, Operand::Field(name) if name == "level");
+    if !is_level(lhs) && !is_level(rhs) {
+        return None;
+    }
+    let a = display(l).parse::<Level>().ok()?;
+    let b = display(r).parse::<Level>().ok()?;
+    Some(a.cmp(&b))
+}
+
+fn as_number(val: &Value) -> Option<f64> {
+    match val {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+fn display(val: &Value) -> String {
+    match val {
+        Value::String(s) => s.clone(),
+        _ => val.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TsFormat;
+    use crate::schema::Schema;
+    use serde_json::json;
+
+    fn record(value: Value) -> LogRecord {
+        let mapping = Schema::Logstash.field_mapping();
+        LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap()
+    }
+
+    fn matches(expr: &str, value: Value) -> bool {
+        Filter::compile(expr).unwrap().matches(&record(value))
+    }
+
+    // --- Comparison tests ---
+
+    #[test]
+    fn numeric_comparison() {
+        let value = json!({"message": "hi", "status": 503});
+        assert!(matches("status >= 500", value.clone()));
+        assert!(matches("status > 500", value.clone()));
+        assert!(!matches("status < 500", value.clone()));
+        assert!(matches("status == 503", value.clone()));
+        assert!(matches("status != 200", value));
+    }
+
+    #[test]
+    fn numeric_string_compares_as_number() {
+        assert!(matches("status >= 500", json!({"status": "503"})));
+        assert!(!matches("status >= 500", json!({"status": "99"})));
+    }
+
+    #[test]
+    fn string_equality() {
+        let value = json!({"message": "hi", "env": "prod"});
+        assert!(matches(r#"env == "prod""#, value.clone()));
+        assert!(matches("env == 'prod'", value.clone()));
+        assert!(!matches(r#"env == "dev""#, value));
+    }
+
+    #[test]
+    fn level_compares_by_severity() {
+        let value = json!({"level": "ERROR", "message": "boom"});
+        assert!(matches(r#"level >= "warn""#, value.clone()));
+        assert!(!matches(r#"level < "warn""#, value.clone()));
+        assert!(matches(r#"level == "error""#, value));
+    }
+
+    #[test]
+    fn missing_field_comparisons() {
+        let value = json!({"message": "hi"});
+        assert!(!matches("status >= 500", value.clone()));
+        assert!(!matches("status < 500", value.clone()));
+        assert!(!matches("status == 500", value.clone()));
+        assert!(matches("status != 500", value.clone()));
+        assert!(matches("status == null", value));
+    }
+
+    #[test]
+    fn boolean_literals() {
+        let value = json!({"cached": true});
+        assert!(matches("cached == true", value.clone()));
+        assert!(!matches("cached == false", value));
+    }
+
+    // --- Regex match tests ---
+
+    #[test]
+    fn regex_match_on_logger() {
+        let value = json!({"logger_name": "com.acme.billing.Invoice", "message": "hi"});
+        assert!(matches(r#"logger ~ "com.acme.*""#, value.clone()));
+        assert!(!matches(r#"logger ~ "^org\\.""#, value.clone()));
+        assert!(matches(r#"logger !~ "^org\\.""#, value));
+    }
+
+    #[test]
+    fn regex_match_missing_field_is_false() {
+        assert!(!matches(r#"logger ~ ".*""#, json!({"message": "hi"})));
+    }
+
+    // --- Logical operator tests ---
+
+    #[test]
+    fn logical_and_or_not() {
+        let value = json!({"message": "hi", "status": 500, "logger_name": "com.acme.Api"});
+        assert!(matches(
+            r#"status >= 500 && logger ~ "com.acme.*" && !exists(user_id)"#,
+            value.clone()
+        ));
+        assert!(!matches("status >= 500 && exists(user_id)", value.clone()));
+        assert!(matches("status == 200 || status == 500", value.clone()));
+        assert!(matches("!(status == 200)", value));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let value = json!({"a": 1, "b": 0, "c": 1});
+        assert!(matches("a == 1 || b == 1 && c == 0", value.clone()));
+        assert!(!matches("(a == 1 || b == 1) && c == 0", value));
+    }
+
+    #[test]
+    fn exists_and_truthy() {
+        let value = json!({"message": "hi", "flag": false, "user": {"id": 7}});
+        assert!(matches("exists(flag)", value.clone()));
+        assert!(!matches("flag", value.clone()));
+        assert!(matches("exists(user.id)", value.clone()));
+        assert!(matches("user.id", value.clone()));
+        assert!(!matches("exists(user.name)", value));
+    }
+
+    // --- Field resolution tests ---
+
+    #[test]
+    fn nested_path_lookup() {
+        let value = json!({"message": "hi", "http": {"status": 404}});
+        assert!(matches("http.status == 404", value));
+    }
+
+    #[test]
+    fn at_sign_field_names() {
+        let value = json!({"message": "hi", "@version": "1"});
+        assert!(matches(r#"@version == "1""#, value));
+    }
+
+    // --- Error tests ---
+
+    #[test]
+    fn compile_errors() {
+        for expr in [
+            "",
+            "status >=",
+            "status = 500",
+            "(status == 500",
+            "status == 500)",
+            r#"logger ~ "[""#,
+            "logger ~ foo",
+            r#"message == "unterminated"#,
+            "status & 1",
+            "exists()",
+        ] {
+            let result = Filter::compile(expr);
+            assert!(result.is_err(), "expected error for {expr:?}");
+        }
+    }
+
+    #[test]
+    fn compile_error_message() {
+        let err = Filter::compile("status >=").unwrap_err();
+        assert!(format!("{err}").contains("invalid --where expression"));
+    }
+}
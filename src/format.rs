@@ -6,13 +6,14 @@ use serde_json::Value;
 use crate::cli::Args;
 use crate::color::ColorConfig;
 use crate::record::LogRecord;
+use crate::search::Search;
 
 /// A parsed token from a format template.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FormatToken {
     /// Literal text to output as-is.
     Literal(String),
-    /// A canonical field placeholder: level, timestamp, logger, message.
+    /// A canonical field placeholder: level, timestamp, logger, message, _file, _prefix.
     Field(CanonicalField),
     /// A custom (non-canonical) field placeholder by name.
     CustomField(String),
@@ -25,6 +26,10 @@ pub enum CanonicalField {
     Timestamp,
     Logger,
     Message,
+    /// The input file a record was read from (`{_file}`).
+    File,
+    /// Text preceding the JSON object on a record's line (`{_prefix}`).
+    Prefix,
 }
 
 /// Parse a format template string into a sequence of tokens.
@@ -60,6 +65,8 @@ pub fn parse_template(template: &str) -> Vec<FormatToken> {
                     "timestamp" => FormatToken::Field(CanonicalField::Timestamp),
                     "logger" => FormatToken::Field(CanonicalField::Logger),
                     "message" => FormatToken::Field(CanonicalField::Message),
+                    "_file" => FormatToken::Field(CanonicalField::File),
+                    "_prefix" => FormatToken::Field(CanonicalField::Prefix),
                     _ => FormatToken::CustomField(field_name),
                 };
                 tokens.push(token);
@@ -91,6 +98,8 @@ pub struct RenderContext {
     pub omit_fields: HashSet<String>,
     pub add_fields: HashSet<String>,
     pub template_custom_fields: HashSet<String>,
+    /// Active `--grep` search, used to highlight matched spans.
+    pub search: Option<Search>,
 }
 
 impl RenderContext {
@@ -109,6 +118,31 @@ impl RenderContext {
             omit_fields,
             add_fields,
             template_custom_fields,
+            search: None,
+        }
+    }
+
+    /// Attach a `--grep` search whose matches should be highlighted.
+    pub fn with_search(mut self, search: Option<Search>) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Highlight search matches in an already-sanitized field value, if the
+    /// field is one being searched.
+    fn highlight(&self, field: &str, text: String, color: &ColorConfig) -> String {
+        match &self.search {
+            Some(search) if search.highlights(field) => search.highlight(&text, color),
+            _ => text,
+        }
+    }
+
+    /// Style an extra field value, highlighting search matches in place of the
+    /// value color when the field is being searched.
+    fn style_extra_value(&self, field: &str, text: &str, color: &ColorConfig) -> String {
+        match &self.search {
+            Some(search) if search.highlights(field) => search.highlight(text, color),
+            _ => color.style_extra_value(text),
         }
     }
 }
@@ -118,6 +152,7 @@ impl RenderContext {
 /// Handles:
 /// - Field substitution from the record
 /// - Color styling for the level field
+/// - Highlighting of `--grep` matches
 /// - `--raw-json` mode (outputs the original JSON)
 /// - `--add-fields` / `--omit-fields` for controlling extra field output
 /// - `--expanded` mode (extras on separate lines vs same line)
@@ -150,16 +185,28 @@ pub fn render(
                     }
                     CanonicalField::Logger => {
                         let raw = record.logger.clone().unwrap_or_default();
-                        let formatted = match args.logger_format {
-                            crate::cli::LoggerFormat::ShortDots => shorten_logger_dots(&raw),
-                            crate::cli::LoggerFormat::AsIs => raw,
+                        let truncated = if record.logger_is_source {
+                            // Source locations are shortened by directory, not by dots
+                            truncate_source_left(&raw, args.logger_length)
+                        } else {
+                            let formatted = match args.logger_format {
+                                crate::cli::LoggerFormat::ShortDots => shorten_logger_dots(&raw),
+                                crate::cli::LoggerFormat::AsIs => raw,
+                            };
+                            truncate_logger_left(&formatted, args.logger_length)
                         };
-                        let truncated =
-                            truncate_logger_left(&formatted, args.logger_length);
                         sanitize_control_chars(&truncated)
                     }
-                    CanonicalField::Message => {
-                        sanitize_control_chars(&record.message.clone().unwrap_or_default())
+                    CanonicalField::Message => ctx.highlight(
+                        "message",
+                        sanitize_control_chars(&record.message.clone().unwrap_or_default()),
+                        color,
+                    ),
+                    CanonicalField::File => {
+                        sanitize_control_chars(&record.file.clone().unwrap_or_default())
+                    }
+                    CanonicalField::Prefix => {
+                        sanitize_control_chars(&record.prefix.clone().unwrap_or_default())
                     }
                 };
                 line.push_str(&value);
@@ -168,9 +215,9 @@ pub fn render(
                 let value = record
                     .extras
                     .get(name)
-                    .map(|v| sanitize_control_chars(&format_extra_value(v)))
+                    .map(display_value)
                     .unwrap_or_default();
-                line.push_str(&value);
+                line.push_str(&ctx.highlight(name, value, color));
             }
         }
     }
@@ -192,7 +239,7 @@ pub fn render(
                 line.push_str(&format!(
                     "  {}: {}",
                     color.style_extra_key(&sanitize_control_chars(k)),
-                    color.style_extra_value(&sanitize_control_chars(&format_extra_value(v)))
+                    ctx.style_extra_value(k, &display_value(v), color)
                 ));
             }
         } else {
@@ -203,7 +250,7 @@ pub fn render(
                     format!(
                         "{}={}",
                         color.style_extra_key(&sanitize_control_chars(k)),
-                        color.style_extra_value(&sanitize_control_chars(&format_extra_value(v)))
+                        ctx.style_extra_value(k, &display_value(v), color)
                     )
                 })
                 .collect();
@@ -293,13 +340,20 @@ fn parse_field_list(list: Option<&str>) -> HashSet<String> {
 }
 
 /// Format a JSON value for display as an extra field value.
-fn format_extra_value(val: &Value) -> String {
+pub fn format_extra_value(val: &Value) -> String {
     match val {
         Value::String(s) => s.clone(),
         _ => val.to_string(),
     }
 }
 
+/// A JSON value as it is displayed: formatted like an extra field value, with
+/// control characters stripped. `--grep` searches this same text, so that
+/// its matches can be highlighted where they appear.
+pub fn display_value(val: &Value) -> String {
+    sanitize_control_chars(&format_extra_value(val))
+}
+
 /// Abbreviate dot-separated logger name segments, keeping only the first character
 /// of each segment except the last.
 ///
@@ -340,14 +394,25 @@ pub fn shorten_logger_dots(name: &str) -> String {
 /// - `truncate_logger_left("c.e.s.MyHandler", 13)` → `"e.s.MyHandler"` (stripped "c.")
 /// - `truncate_logger_left("VeryLongName", 4)` → `"Name"` (hard-truncated)
 pub fn truncate_logger_left(name: &str, max_len: usize) -> String {
+    truncate_left(name, max_len, '.')
+}
+
+/// Truncate a source location (`internal/orders/handler.go:87`) from the left when
+/// it exceeds `max_len`, stripping leading directories the way
+/// [`truncate_logger_left`] strips dot-separated segments.
+pub fn truncate_source_left(location: &str, max_len: usize) -> String {
+    truncate_left(location, max_len, '/')
+}
+
+fn truncate_left(name: &str, max_len: usize, separator: char) -> String {
     if max_len == 0 || name.chars().count() <= max_len {
         return name.to_string();
     }
 
-    // Try stripping leftmost dot-segments one at a time
+    // Try stripping leftmost segments one at a time
     let mut remaining = name;
     while remaining.chars().count() > max_len {
-        if let Some(dot_pos) = remaining.find('.') {
+        if let Some(dot_pos) = remaining.find(separator) {
             let after_dot = &remaining[dot_pos + 1..];
             if after_dot.is_empty() {
                 break;
@@ -400,7 +465,7 @@ mod tests {
         color: &ColorConfig,
         args: &Args,
     ) -> String {
-        let ctx = RenderContext::new(args, tokens);
+        let ctx = RenderContext::new(args, tokens).with_search(Search::from_args(args).unwrap());
         render(record, tokens, color, args, &ctx)
     }
 
@@ -411,17 +476,36 @@ mod tests {
             omit_fields: None,
             color: ColorMode::Never,
             non_json: NonJsonMode::PrintAsIs,
+            find_json: false,
+            multiline: false,
+            join_continuations: false,
+            line_regex: None,
+            line_format: None,
             schema: SchemaChoice::Auto,
             logger_format: LoggerFormat::AsIs,
             logger_length: 0,
             ts_format: TsFormat::Full,
             min_level: None,
+            where_expr: None,
+            grep: None,
+            grep_fields: None,
+            ignore_case: false,
+            invert: false,
+            after_context: None,
+            before_context: None,
+            context: None,
+            since: None,
+            until: None,
             raw_json: false,
             expanded: false,
             key_color: crate::cli::CliColor::Magenta,
             value_color: crate::cli::CliColor::Cyan,
             tz: "utc".to_string(),
             follow: false,
+            lines: None,
+            from_end: false,
+            retry: false,
+            merge: false,
             output: None,
             completions: None,
             files: vec![],
@@ -437,11 +521,15 @@ mod tests {
         LogRecord {
             level,
             timestamp: timestamp.map(String::from),
+            datetime: None,
             logger: logger.map(String::from),
+            logger_is_source: false,
             message: message.map(String::from),
             stack_trace: None,
             extras: BTreeMap::new(),
             raw: json!({}),
+            file: None,
+            prefix: None,
         }
     }
 
@@ -528,6 +616,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_file_placeholder() {
+        let tokens = parse_template("[{_file}] {message}");
+        assert_eq!(tokens[1], FormatToken::Field(CanonicalField::File));
+    }
+
+    #[test]
+    fn parse_prefix_placeholder() {
+        let tokens = parse_template("{_prefix} {message}");
+        assert_eq!(tokens[0], FormatToken::Field(CanonicalField::Prefix));
+    }
+
     // --- Rendering tests ---
 
     #[test]
@@ -571,6 +671,33 @@ mod tests {
         assert_eq!(output, "  [] just a message");
     }
 
+    #[test]
+    fn render_file_placeholder() {
+        let mut record = make_record(None, None, None, Some("hello"));
+        let tokens = parse_template("{_file}: {message}");
+        let color = ColorConfig::with_enabled(false);
+        let args = default_args();
+        assert_eq!(test_render(&record, &tokens, &color, &args), ": hello");
+        record.file = Some("api.log".to_string());
+        assert_eq!(
+            test_render(&record, &tokens, &color, &args),
+            "api.log: hello"
+        );
+    }
+
+    #[test]
+    fn render_prefix_placeholder() {
+        let mut record = make_record(None, None, None, Some("hello"));
+        record.prefix = Some("[pod/web/app]".to_string());
+        let tokens = parse_template("{_prefix} {message}");
+        let color = ColorConfig::with_enabled(false);
+        let args = default_args();
+        assert_eq!(
+            test_render(&record, &tokens, &color, &args),
+            "[pod/web/app] hello"
+        );
+    }
+
     #[test]
     fn render_with_extras_expanded_mode() {
         let mut record = make_record(Some(Level::Info), None, None, Some("test"));
@@ -659,11 +786,15 @@ mod tests {
         let record = LogRecord {
             level: Some(Level::Info),
             timestamp: None,
+            datetime: None,
             logger: None,
+            logger_is_source: false,
             message: Some("hello".to_string()),
             stack_trace: None,
             extras: BTreeMap::new(),
             raw: raw.clone(),
+            file: None,
+            prefix: None,
         };
         let tokens = parse_template("{level}: {message}");
         let color = ColorConfig::with_enabled(false);
@@ -1061,6 +1192,12 @@ mod tests {
         assert_eq!(shorten_logger_dots("org.apache.Logger"), "o.a.Logger");
     }
 
+    #[test]
+    fn shorten_logger_dots_with_slashes_and_colons() {
+        assert_eq!(shorten_logger_dots("akka://sys/user/a.b"), "a.b");
+        assert_eq!(shorten_logger_dots("svc:db.pool"), "s.pool");
+    }
+
     // --- truncate_logger_left tests ---
 
     #[test]
@@ -1079,6 +1216,18 @@ mod tests {
         assert_eq!(truncate_logger_left("c.e.s.MyHandler", 13), "e.s.MyHandler");
     }
 
+    #[test]
+    fn truncate_source_left_strips_directories() {
+        assert_eq!(
+            truncate_source_left("internal/orders/handler.go:87", 20),
+            "orders/handler.go:87"
+        );
+        assert_eq!(
+            truncate_source_left("internal/orders/handler.go:87", 15),
+            "handler.go:87"
+        );
+    }
+
     #[test]
     fn truncate_logger_left_strip_multiple_segments() {
         // "com.example.service.Handler" is 27 chars, max 15 -> strip "com." -> "example.service.Handler" (23) -> strip "example." -> "service.Handler" (15)
@@ -1158,6 +1307,26 @@ mod tests {
         assert_eq!(output, "[SimpleLogger] msg");
     }
 
+    #[test]
+    fn render_source_location_logger() {
+        let mut record = make_record(
+            Some(Level::Info),
+            None,
+            Some("internal/orders/handler.go:87"),
+            Some("hello"),
+        );
+        record.logger_is_source = true;
+        let tokens = parse_template("[{logger}] {message}");
+        let color = ColorConfig::with_enabled(false);
+        let mut args = default_args();
+        args.logger_format = LoggerFormat::ShortDots;
+        let output = test_render(&record, &tokens, &color, &args);
+        assert_eq!(output, "[internal/orders/handler.go:87] hello");
+        args.logger_length = 15;
+        let output = test_render(&record, &tokens, &color, &args);
+        assert_eq!(output, "[handler.go:87] hello");
+    }
+
     #[test]
     fn render_logger_length_truncation() {
         let record = make_record(
@@ -1269,4 +1438,66 @@ mod tests {
         assert!(!output.contains("\x1b["));
         assert!(output.contains("\n  host: server1"));
     }
+
+    // --- Grep highlighting tests ---
+
+    #[test]
+    fn render_highlights_message_matches() {
+        let record = make_record(
+            Some(Level::Info),
+            None,
+            None,
+            Some("request timeout after 30s"),
+        );
+        let tokens = parse_template("{level}: {message}");
+        let color = ColorConfig::with_enabled(true);
+        let mut args = default_args();
+        args.grep = Some("timeout".to_string());
+        let output = test_render(&record, &tokens, &color, &args);
+        assert!(output.contains(&color.style_match("timeout")));
+        assert!(output.contains(" after 30s"));
+    }
+
+    #[test]
+    fn render_highlight_plain_without_color() {
+        let record = make_record(Some(Level::Info), None, None, Some("request timeout"));
+        let tokens = parse_template("{level}: {message}");
+        let color = ColorConfig::with_enabled(false);
+        let mut args = default_args();
+        args.grep = Some("timeout".to_string());
+        let output = test_render(&record, &tokens, &color, &args);
+        assert_eq!(output, "INFO: request timeout");
+    }
+
+    #[test]
+    fn render_highlights_selected_extra_fields() {
+        let mut record = make_record(Some(Level::Info), None, None, Some("db query"));
+        record
+            .extras
+            .insert("host".to_string(), json!("db-primary"));
+        record.extras.insert("pool".to_string(), json!("db-pool"));
+        let tokens = parse_template("{level}: {message}");
+        let color = ColorConfig::with_enabled(true);
+        let mut args = default_args();
+        args.grep = Some("db".to_string());
+        args.grep_fields = Some("host".to_string());
+        args.add_fields = Some("host,pool".to_string());
+        let output = test_render(&record, &tokens, &color, &args);
+        // host is searched and highlighted; message and pool are not
+        assert!(output.contains(&format!("{}-primary", color.style_match("db"))));
+        assert!(output.contains(&color.style_extra_value("db-pool")));
+        assert!(output.contains(": db query"));
+    }
+
+    #[test]
+    fn render_inverted_grep_does_not_highlight() {
+        let record = make_record(Some(Level::Info), None, None, Some("request timeout"));
+        let tokens = parse_template("{message}");
+        let color = ColorConfig::with_enabled(true);
+        let mut args = default_args();
+        args.grep = Some("timeout".to_string());
+        args.invert = true;
+        let output = test_render(&record, &tokens, &color, &args);
+        assert_eq!(output, "request timeout");
+    }
 }
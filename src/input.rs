@@ -1,17 +1,31 @@
 use std::fs::{self, File};
-use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Stdin};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::thread;
-use std::time::Duration;
 
+use chrono::{DateTime, FixedOffset};
+
+use crate::decompress::{Compression, maybe_decompress};
 use crate::error::JlError;
+use crate::watch::{FileWatcher, POLL_INTERVAL};
+
+/// Stop bisecting once the candidate range is smaller than this many bytes;
+/// the remainder is scanned linearly.
+const SEEK_MIN_SPAN: u64 = 4096;
+
+/// Maximum number of lines inspected at each bisection probe when looking for
+/// a line with a timestamp.
+const SEEK_PROBE_LINES: usize = 64;
 
 pub trait LineSource {
     fn next_line(&mut self) -> Result<Option<String>, JlError>;
 }
 
 pub struct StdinSource {
-    reader: BufReader<Stdin>,
+    reader: Box<dyn BufRead>,
+    /// Whether the stream has been checked for compression yet. Detection is
+    /// deferred to the first read so that constructing the source never blocks.
+    detected: bool,
 }
 
 impl Default for StdinSource {
@@ -23,13 +37,19 @@ impl Default for StdinSource {
 impl StdinSource {
     pub fn new() -> Self {
         Self {
-            reader: BufReader::new(io::stdin()),
+            reader: Box::new(BufReader::new(io::stdin())),
+            detected: false,
         }
     }
 }
 
 impl LineSource for StdinSource {
     fn next_line(&mut self) -> Result<Option<String>, JlError> {
+        if !self.detected {
+            let reader = std::mem::replace(&mut self.reader, Box::new(io::empty()));
+            self.reader = maybe_decompress(reader)?;
+            self.detected = true;
+        }
         let mut line = String::new();
         let bytes_read = self.reader.read_line(&mut line)?;
         if bytes_read == 0 {
@@ -47,23 +67,101 @@ impl LineSource for StdinSource {
     }
 }
 
+/// Reads a file line by line, decompressing gzip, zstd and bzip2 files
+/// transparently based on their magic bytes.
 pub struct FileSource {
-    reader: BufReader<File>,
+    reader: FileReader,
+}
+
+enum FileReader {
+    /// Uncompressed; supports seeking.
+    Plain(BufReader<File>),
+    Decompressed(Box<dyn BufRead>),
 }
 
 impl FileSource {
     pub fn new(path: &Path) -> Result<Self, JlError> {
-        let file = File::open(path)?;
-        Ok(Self {
-            reader: BufReader::new(file),
-        })
+        let mut reader = BufReader::new(File::open(path)?);
+        let reader = match Compression::detect(&mut reader)? {
+            Some(compression) => FileReader::Decompressed(compression.decoder(reader)?),
+            None => FileReader::Plain(reader),
+        };
+        Ok(Self { reader })
+    }
+}
+
+impl FileSource {
+    /// Move the read position close to the first line whose timestamp is at or
+    /// after `target`, assuming the file is sorted by time.
+    ///
+    /// Bisects on byte offsets: at each probe the next complete line that
+    /// `line_time` can date is inspected, and the lower bound only advances
+    /// past lines known to be earlier than `target`. The final position is
+    /// always a line start at or before the first matching line, so callers
+    /// must still filter records individually. Compressed and non-regular
+    /// files (pipes, devices) are left untouched.
+    pub fn seek_to_time(
+        &mut self,
+        target: DateTime<FixedOffset>,
+        mut line_time: impl FnMut(&str) -> Option<DateTime<FixedOffset>>,
+    ) -> Result<(), JlError> {
+        let FileReader::Plain(reader) = &mut self.reader else {
+            return Ok(());
+        };
+        let meta = reader.get_ref().metadata()?;
+        if !meta.is_file() {
+            return Ok(());
+        }
+        let start = reader.stream_position()?;
+        let mut lo = start;
+        let mut hi = meta.len();
+        let mut buf = Vec::new();
+
+        while hi.saturating_sub(lo) > SEEK_MIN_SPAN {
+            let mid = lo + (hi - lo) / 2;
+            reader.seek(SeekFrom::Start(mid))?;
+            // Skip the (probably partial) line we landed in
+            buf.clear();
+            let mut pos = mid + reader.read_until(b'\n', &mut buf)? as u64;
+
+            let mut probe = None;
+            for _ in 0..SEEK_PROBE_LINES {
+                if pos >= hi {
+                    break;
+                }
+                buf.clear();
+                let n = reader.read_until(b'\n', &mut buf)? as u64;
+                if n == 0 {
+                    break;
+                }
+                pos += n;
+                if let Some(ts) = line_time(String::from_utf8_lossy(&buf).trim_end()) {
+                    probe = Some((ts, pos));
+                    break;
+                }
+            }
+
+            match probe {
+                // Everything up to and including this line is before the target
+                Some((ts, line_end)) if ts < target => lo = line_end,
+                // At or after the target, or no dated line found: search lower half
+                _ => hi = mid,
+            }
+        }
+
+        reader.seek(SeekFrom::Start(lo))?;
+        Ok(())
     }
 }
 
 impl LineSource for FileSource {
     fn next_line(&mut self) -> Result<Option<String>, JlError> {
+        let reader: &mut dyn BufRead = match &mut self.reader {
+            FileReader::Plain(reader) => reader,
+            FileReader::Decompressed(reader) => reader,
+        };
         let mut line = String::new();
-        let bytes_read = self.reader.read_line(&mut line)?;
+        let bytes_read = reader.read_line(&mut line)?;
         if bytes_read == 0 {
             Ok(None)
         } else {
@@ -86,15 +184,19 @@ impl LineSource for FileSource {
 ///
 /// On unix systems, file rotation is detected by comparing inode numbers,
 /// so a replaced file is always read from the beginning even if it is already
-/// larger than the previous read position.
+/// larger than the previous read position. A source created with
+/// [`FollowSource::waiting`] treats the file's first appearance the same way.
 pub struct FollowSource {
-    reader: BufReader<File>,
+    /// `None` while waiting for a missing file to be created.
+    reader: Option<BufReader<File>>,
     path: PathBuf,
     #[cfg(unix)]
     inode: u64,
     /// Count of consecutive metadata failures after successful file opens.
     /// Used to detect persistent errors and avoid silent infinite stalls.
     metadata_failures: u32,
+    /// Text read after the last newline, completed by a later read.
+    partial: String,
 }
 
 impl FollowSource {
@@ -107,33 +209,130 @@ impl FollowSource {
             file.metadata()?.ino()
         };
         Ok(Self {
-            reader: BufReader::new(file),
+            reader: Some(BufReader::new(file)),
             path: path.to_path_buf(),
             #[cfg(unix)]
             inode,
             metadata_failures: 0,
+            partial: String::new(),
         })
     }
 
-    /// Create a new `FollowSource` starting from the end of the file.
-    /// Only new lines appended after this point will be read.
-    #[cfg(test)]
-    pub fn new_from_end(path: &Path) -> Result<Self, JlError> {
-        let file = File::open(path)?;
-        #[cfg(unix)]
-        let inode = {
-            use std::os::unix::fs::MetadataExt;
-            file.metadata()?.ino()
-        };
-        let mut reader = BufReader::new(file);
-        reader.seek(SeekFrom::End(0))?;
-        Ok(Self {
-            reader,
+    /// Create a `FollowSource` for a file that does not exist yet. No lines
+    /// are produced until the file is created, after which it is read from
+    /// the beginning.
+    pub fn waiting(path: &Path) -> Self {
+        Self {
+            reader: None,
             path: path.to_path_buf(),
+            // Inode 0 is never a real file, so the first open counts as a rotation
             #[cfg(unix)]
-            inode,
+            inode: 0,
             metadata_failures: 0,
-        })
+            partial: String::new(),
+        }
+    }
+
+    /// Create a new `FollowSource` starting with the last `lines` lines of the
+    /// file, like `tail -n`. With `lines == 0` only new lines appended after
+    /// this point will be read.
+    pub fn new_from_end(path: &Path, lines: usize) -> Result<Self, JlError> {
+        let mut source = Self::new(path)?;
+        if let Some(reader) = source.reader.as_mut() {
+            let offset = tail_offset(reader.get_mut(), lines)?;
+            reader.seek(SeekFrom::Start(offset))?;
+        }
+        Ok(source)
+    }
+
+    /// Return the next complete line if one is available, without waiting.
+    ///
+    /// Returns `None` at EOF; the caller decides how to wait for more data and
+    /// should call [`FollowSource::refresh`] when the file may have been
+    /// rotated. A trailing partial line is buffered until its newline arrives.
+    pub fn poll_line(&mut self) -> Result<Option<String>, JlError> {
+        let Some(reader) = self.reader.as_mut() else {
+            return Ok(None);
+        };
+        loop {
+            let mut line = String::new();
+            let bytes_read = reader.read_line(&mut line)?;
+            if bytes_read == 0 {
+                return Ok(None);
+            }
+            // Check if we got a complete line (ends with newline)
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+                if self.partial.is_empty() {
+                    return Ok(Some(line));
+                } else {
+                    self.partial.push_str(&line);
+                    return Ok(Some(std::mem::take(&mut self.partial)));
+                }
+            } else {
+                // Partial line (no trailing newline) - buffer and continue
+                // reading to get the rest before returning
+                self.partial.push_str(&line);
+            }
+        }
+    }
+
+    /// Re-open the path to pick up new data if the file was replaced/rotated,
+    /// or just continue reading from the current position. While the path is
+    /// missing (deleted, or not created yet) the current handle is kept.
+    pub fn refresh(&mut self) -> Result<(), JlError> {
+        let Ok(file) = File::open(&self.path) else {
+            return Ok(());
+        };
+        let current_pos = match self.reader.as_mut() {
+            Some(reader) => reader.stream_position()?,
+            None => 0,
+        };
+        if let Ok(new_meta) = file.metadata() {
+            self.metadata_failures = 0;
+            let mut new_reader = BufReader::new(file);
+            if self.is_file_rotated(&new_meta, current_pos) {
+                // File was truncated or replaced; start from beginning
+                new_reader.seek(SeekFrom::Start(0))?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::MetadataExt;
+                    self.inode = new_meta.ino();
+                }
+                // Discard any partial line buffered from the old file
+                self.partial.clear();
+            } else {
+                new_reader.seek(SeekFrom::Start(current_pos))?;
+            }
+            self.reader = Some(new_reader);
+        } else {
+            self.metadata_failures += 1;
+            if self.metadata_failures >= 10 {
+                // Persistent metadata failure; switch to the new file
+                // handle. Without metadata we cannot check inodes, but
+                // we can detect truncation by probing file length.
+                let path_display =
+                    crate::format::sanitize_control_chars(&self.path.display().to_string());
+                eprintln!("jl: warning: repeated metadata failures for {path_display}");
+                let mut new_reader = BufReader::new(file);
+                let file_len = new_reader.seek(SeekFrom::End(0))?;
+                if file_len < current_pos {
+                    // File is shorter than our position – likely
+                    // truncated or replaced; start from the beginning.
+                    new_reader.seek(SeekFrom::Start(0))?;
+                    // Discard any partial line buffered from the old file
+                    self.partial.clear();
+                } else {
+                    new_reader.seek(SeekFrom::Start(current_pos))?;
+                }
+                self.reader = Some(new_reader);
+                self.metadata_failures = 0;
+            }
+        }
+        Ok(())
     }
 
     /// Check whether the file at our path has been replaced (different inode)
@@ -152,89 +351,112 @@ impl FollowSource {
     }
 }
 
+/// Find the byte offset where the last `lines` lines of a file start by
+/// scanning backward from EOF in fixed-size chunks. A final newline ends the
+/// last line rather than starting a new one. Returns 0 when the file has
+/// fewer lines than requested.
+fn tail_offset(file: &mut File, lines: usize) -> io::Result<u64> {
+    const CHUNK: u64 = 8192;
+    let len = file.seek(SeekFrom::End(0))?;
+    if lines == 0 {
+        return Ok(len);
+    }
+    let mut buf = vec![0; CHUNK as usize];
+    let mut pos = len;
+    let mut found = 0;
+    while pos > 0 {
+        let size = CHUNK.min(pos);
+        pos -= size;
+        file.seek(SeekFrom::Start(pos))?;
+        let chunk = &mut buf[..size as usize];
+        file.read_exact(chunk)?;
+        for (i, byte) in chunk.iter().enumerate().rev() {
+            let line_start = pos + i as u64 + 1;
+            if *byte == b'\n' && line_start != len {
+                found += 1;
+                if found == lines {
+                    return Ok(line_start);
+                }
+            }
+        }
+    }
+    Ok(0)
+}
+
 impl LineSource for FollowSource {
     fn next_line(&mut self) -> Result<Option<String>, JlError> {
-        let mut partial = String::new();
         loop {
-            let mut line = String::new();
-            let bytes_read = self.reader.read_line(&mut line)?;
-            if bytes_read == 0 {
-                // EOF reached - sleep and retry
-                thread::sleep(Duration::from_millis(200));
-                // Re-open to pick up new data if the file was replaced/rotated,
-                // or just continue reading from the current position
-                if let Ok(file) = File::open(&self.path) {
-                    let current_pos = self.reader.stream_position()?;
-                    if let Ok(new_meta) = file.metadata() {
-                        self.metadata_failures = 0;
-                        let mut new_reader = BufReader::new(file);
-                        if self.is_file_rotated(&new_meta, current_pos) {
-                            // File was truncated or replaced; start from beginning
-                            new_reader.seek(SeekFrom::Start(0))?;
-                            #[cfg(unix)]
-                            {
-                                use std::os::unix::fs::MetadataExt;
-                                self.inode = new_meta.ino();
-                            }
-                            // Discard any partial line buffered from the old file
-                            partial.clear();
-                        } else {
-                            new_reader.seek(SeekFrom::Start(current_pos))?;
-                        }
-                        self.reader = new_reader;
-                    } else {
-                        self.metadata_failures += 1;
-                        if self.metadata_failures >= 10 {
-                            // Persistent metadata failure; switch to the new file
-                            // handle. Without metadata we cannot check inodes, but
-                            // we can detect truncation by probing file length.
-                            let path_display = crate::format::sanitize_control_chars(
-                                &self.path.display().to_string(),
-                            );
-                            eprintln!("jl: warning: repeated metadata failures for {path_display}");
-                            let mut new_reader = BufReader::new(file);
-                            let file_len = new_reader.seek(SeekFrom::End(0))?;
-                            if file_len < current_pos {
-                                // File is shorter than our position – likely
-                                // truncated or replaced; start from the beginning.
-                                new_reader.seek(SeekFrom::Start(0))?;
-                                // Discard any partial line buffered from the old file
-                                partial.clear();
-                            } else {
-                                new_reader.seek(SeekFrom::Start(current_pos))?;
-                            }
-                            self.reader = new_reader;
-                            self.metadata_failures = 0;
-                        }
-                    }
-                }
-                continue;
+            if let Some(line) = self.poll_line()? {
+                return Ok(Some(line));
             }
-            // Check if we got a complete line (ends with newline)
-            if line.ends_with('\n') {
-                line.pop();
-                if line.ends_with('\r') {
-                    line.pop();
-                }
-                if partial.is_empty() {
-                    return Ok(Some(line));
-                } else {
-                    partial.push_str(&line);
-                    return Ok(Some(std::mem::take(&mut partial)));
-                }
-            } else {
-                // Partial line (no trailing newline) - buffer and continue
-                // reading to get the rest before returning
-                partial.push_str(&line);
+            thread::sleep(POLL_INTERVAL);
+            self.refresh()?;
+        }
+    }
+}
+
+/// Follows several files at once, interleaving new lines as they arrive.
+///
+/// Files are read round-robin one line at a time, so a busy file cannot
+/// starve the others. Once every file is at EOF, the set blocks on a
+/// [`FileWatcher`] and re-checks only the files it reports as changed.
+pub struct FollowSet {
+    sources: Vec<FollowSource>,
+    watcher: FileWatcher,
+    next: usize,
+}
+
+impl FollowSet {
+    /// Follow the given sources, watching for changes through inotify where
+    /// available and polling otherwise.
+    pub fn new(sources: Vec<FollowSource>) -> Self {
+        let paths = sources.iter().map(|s| s.path.clone()).collect();
+        Self::with_watcher(sources, FileWatcher::new(paths))
+    }
+
+    fn with_watcher(sources: Vec<FollowSource>, watcher: FileWatcher) -> Self {
+        Self {
+            sources,
+            watcher,
+            next: 0,
+        }
+    }
+
+    /// Block until any file has a complete line, returning it together with
+    /// the index of the file it came from. Returns `None` only for an empty set.
+    pub fn next_line(&mut self) -> Result<Option<(usize, String)>, JlError> {
+        if self.sources.is_empty() {
+            return Ok(None);
+        }
+        loop {
+            if let Some(next) = self.poll_line()? {
+                return Ok(Some(next));
+            }
+            for index in self.watcher.wait()? {
+                self.sources[index].refresh()?;
             }
         }
     }
+
+    /// Return a complete line if any file has one ready, without waiting.
+    /// Files take turns so that a busy file cannot starve the others.
+    pub fn poll_line(&mut self) -> Result<Option<(usize, String)>, JlError> {
+        for _ in 0..self.sources.len() {
+            let index = self.next;
+            self.next = (self.next + 1) % self.sources.len();
+            if let Some(line) = self.sources[index].poll_line()? {
+                return Ok(Some((index, line)));
+            }
+        }
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Write;
+    use std::time::Duration;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -321,6 +543,123 @@ mod tests {
         assert_eq!(source.next_line().unwrap(), None);
     }
 
+    // --- seek_to_time tests ---
+
+    fn line_minute(line: &str) -> Option<DateTime<FixedOffset>> {
+        let minute = line.strip_prefix("minute ")?.split(' ').next()?;
+        let secs: i64 = minute.parse::<i64>().ok()? * 60;
+        DateTime::from_timestamp(secs, 0).map(|dt| dt.fixed_offset())
+    }
+
+    fn sorted_file(lines: usize) -> NamedTempFile {
+        let mut tmp = NamedTempFile::new().unwrap();
+        for i in 0..lines {
+            writeln!(tmp, "minute {i} padding to make the line a bit longer").unwrap();
+        }
+        tmp.flush().unwrap();
+        tmp
+    }
+
+    #[test]
+    fn seek_to_time_skips_earlier_lines() {
+        let tmp = sorted_file(20_000);
+        let mut source = FileSource::new(tmp.path()).unwrap();
+        let target = line_minute("minute 15000").unwrap();
+        source.seek_to_time(target, line_minute).unwrap();
+
+        let first = source.next_line().unwrap().unwrap();
+        let first_minute = line_minute(&first).unwrap();
+        assert!(first_minute <= target, "seeked past the target: {first}");
+        // Landed within a few KiB of the target rather than at the start
+        assert!(
+            first_minute.timestamp() / 60 > 14_800,
+            "seek too short: {first}"
+        );
+
+        // Every line at or after the target is still read
+        let mut remaining = vec![first];
+        while let Some(line) = source.next_line().unwrap() {
+            remaining.push(line);
+        }
+        assert!(remaining.iter().any(|l| l.starts_with("minute 15000 ")));
+        assert!(remaining.last().unwrap().starts_with("minute 19999 "));
+    }
+
+    #[test]
+    fn seek_to_time_before_start_keeps_position() {
+        let tmp = sorted_file(5_000);
+        let mut source = FileSource::new(tmp.path()).unwrap();
+        let target = DateTime::from_timestamp(-60, 0).unwrap().fixed_offset();
+        source.seek_to_time(target, line_minute).unwrap();
+        assert!(
+            source
+                .next_line()
+                .unwrap()
+                .unwrap()
+                .starts_with("minute 0 ")
+        );
+    }
+
+    #[test]
+    fn seek_to_time_after_end_reads_tail_only() {
+        let tmp = sorted_file(5_000);
+        let mut source = FileSource::new(tmp.path()).unwrap();
+        let target = line_minute("minute 99999").unwrap();
+        source.seek_to_time(target, line_minute).unwrap();
+        let mut count = 0;
+        while source.next_line().unwrap().is_some() {
+            count += 1;
+        }
+        assert!(
+            count < 200,
+            "expected only the tail to remain, got {count} lines"
+        );
+    }
+
+    #[test]
+    fn seek_to_time_without_timestamps_keeps_position() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        for i in 0..5_000 {
+            writeln!(tmp, "undated line {i}").unwrap();
+        }
+        tmp.flush().unwrap();
+        let mut source = FileSource::new(tmp.path()).unwrap();
+        let target = line_minute("minute 10").unwrap();
+        source.seek_to_time(target, line_minute).unwrap();
+        assert_eq!(
+            source.next_line().unwrap(),
+            Some("undated line 0".to_string())
+        );
+    }
+
+    #[test]
+    fn file_source_decompresses_gzip() {
+        let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        enc.write_all(b"line one\nline two\n").unwrap();
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(&enc.finish().unwrap()).unwrap();
+        tmp.flush().unwrap();
+
+        let mut source = FileSource::new(tmp.path()).unwrap();
+        assert_eq!(source.next_line().unwrap(), Some("line one".to_string()));
+        assert_eq!(source.next_line().unwrap(), Some("line two".to_string()));
+        assert_eq!(source.next_line().unwrap(), None);
+    }
+
+    #[test]
+    fn seek_to_time_skips_compressed_files() {
+        let content: String = (0..5_000).map(|i| format!("minute {i} x\n")).collect();
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(&zstd::encode_all(content.as_bytes(), 0).unwrap())
+            .unwrap();
+        tmp.flush().unwrap();
+
+        let mut source = FileSource::new(tmp.path()).unwrap();
+        let target = line_minute("minute 4000").unwrap();
+        source.seek_to_time(target, line_minute).unwrap();
+        assert_eq!(source.next_line().unwrap(), Some("minute 0 x".to_string()));
+    }
+
     // --- FollowSource tests ---
 
     #[test]
@@ -379,7 +718,7 @@ mod tests {
             f.flush().unwrap();
         }
 
-        let mut source = FollowSource::new_from_end(&path).unwrap();
+        let mut source = FollowSource::new_from_end(&path, 0).unwrap();
 
         // Append new content
         {
@@ -396,6 +735,194 @@ mod tests {
         assert_eq!(line, Some("new line".to_string()));
     }
 
+    fn tail_lines(content: &str, lines: usize) -> Vec<String> {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "{content}").unwrap();
+        tmp.flush().unwrap();
+        let mut source = FollowSource::new_from_end(tmp.path(), lines).unwrap();
+        std::iter::from_fn(|| source.poll_line().unwrap()).collect()
+    }
+
+    #[test]
+    fn new_from_end_keeps_last_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), vec!["b", "c"]);
+        assert_eq!(tail_lines("a\nb\nc\n", 1), vec!["c"]);
+        assert_eq!(tail_lines("a\nb\nc\n", 0), Vec::<String>::new());
+    }
+
+    #[test]
+    fn new_from_end_more_lines_than_file() {
+        assert_eq!(tail_lines("a\nb\n", 10), vec!["a", "b"]);
+        assert_eq!(tail_lines("", 3), Vec::<String>::new());
+    }
+
+    #[test]
+    fn new_from_end_counts_unterminated_last_line() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        write!(tmp, "a\nb\nc").unwrap();
+        tmp.flush().unwrap();
+        let mut source = FollowSource::new_from_end(tmp.path(), 2).unwrap();
+        assert_eq!(source.poll_line().unwrap(), Some("b".to_string()));
+        // The partial last line is held back until its newline arrives
+        assert_eq!(source.poll_line().unwrap(), None);
+        writeln!(tmp).unwrap();
+        tmp.flush().unwrap();
+        assert_eq!(source.poll_line().unwrap(), Some("c".to_string()));
+    }
+
+    #[test]
+    fn new_from_end_spans_chunks() {
+        let content: String = (0..5000).map(|i| format!("line {i}\n")).collect();
+        let lines = tail_lines(&content, 3000);
+        assert_eq!(lines.len(), 3000);
+        assert_eq!(lines[0], "line 2000");
+        assert_eq!(lines[2999], "line 4999");
+    }
+
+    #[test]
+    fn follow_source_poll_buffers_partial_line() {
+        let tmp = NamedTempFile::new().unwrap();
+        let path = tmp.path().to_owned();
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+
+        let mut source = FollowSource::new(&path).unwrap();
+        assert_eq!(source.poll_line().unwrap(), None);
+
+        write!(f, "half").unwrap();
+        f.flush().unwrap();
+        assert_eq!(source.poll_line().unwrap(), None);
+
+        writeln!(f, " and half").unwrap();
+        f.flush().unwrap();
+        assert_eq!(
+            source.poll_line().unwrap(),
+            Some("half and half".to_string())
+        );
+    }
+
+    #[test]
+    fn follow_set_interleaves_files() {
+        let first = NamedTempFile::new().unwrap();
+        let second = NamedTempFile::new().unwrap();
+        let mut set = FollowSet::new(vec![
+            FollowSource::new(first.path()).unwrap(),
+            FollowSource::new(second.path()).unwrap(),
+        ]);
+
+        let append = |path: &Path, line: &str| {
+            let mut f = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+            writeln!(f, "{line}").unwrap();
+        };
+        append(second.path(), "second 1");
+        assert_eq!(set.next_line().unwrap(), Some((1, "second 1".to_string())));
+
+        append(first.path(), "first 1");
+        append(second.path(), "second 2");
+        append(first.path(), "first 2");
+        assert_eq!(set.next_line().unwrap(), Some((0, "first 1".to_string())));
+        assert_eq!(set.next_line().unwrap(), Some((1, "second 2".to_string())));
+        assert_eq!(set.next_line().unwrap(), Some((0, "first 2".to_string())));
+    }
+
+    #[test]
+    fn follow_set_poll_line_does_not_wait() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        let mut set = FollowSet::new(vec![FollowSource::new(tmp.path()).unwrap()]);
+        assert_eq!(set.poll_line().unwrap(), None);
+
+        writeln!(tmp, "ready").unwrap();
+        tmp.flush().unwrap();
+        assert_eq!(set.poll_line().unwrap(), Some((0, "ready".to_string())));
+        assert_eq!(set.poll_line().unwrap(), None);
+    }
+
+    #[test]
+    fn follow_set_polling_fallback() {
+        let tmp = NamedTempFile::new().unwrap();
+        let source = FollowSource::new(tmp.path()).unwrap();
+        let watcher = FileWatcher::polling(vec![tmp.path().to_path_buf()]);
+        let mut set = FollowSet::with_watcher(vec![source], watcher);
+
+        let path = tmp.path().to_owned();
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            let mut f = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+            writeln!(f, "late line").unwrap();
+        });
+        assert_eq!(set.next_line().unwrap(), Some((0, "late line".to_string())));
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn follow_set_picks_up_rotated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "old 1\n").unwrap();
+
+        let mut set = FollowSet::new(vec![FollowSource::new(&path).unwrap()]);
+        assert_eq!(set.next_line().unwrap(), Some((0, "old 1".to_string())));
+
+        std::fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        std::fs::write(&path, "new 1\n").unwrap();
+        assert_eq!(set.next_line().unwrap(), Some((0, "new 1".to_string())));
+    }
+
+    #[test]
+    fn waiting_source_reads_file_once_created() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("later.log");
+        let mut source = FollowSource::waiting(&path);
+        assert_eq!(source.poll_line().unwrap(), None);
+        source.refresh().unwrap();
+        assert_eq!(source.poll_line().unwrap(), None);
+
+        std::fs::write(&path, "first\nsecond\n").unwrap();
+        source.refresh().unwrap();
+        assert_eq!(source.poll_line().unwrap(), Some("first".to_string()));
+        assert_eq!(source.poll_line().unwrap(), Some("second".to_string()));
+    }
+
+    #[test]
+    fn follow_source_survives_delete_and_recreate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "old\n").unwrap();
+        let mut source = FollowSource::new(&path).unwrap();
+        assert_eq!(source.poll_line().unwrap(), Some("old".to_string()));
+
+        std::fs::remove_file(&path).unwrap();
+        source.refresh().unwrap();
+        assert_eq!(source.poll_line().unwrap(), None);
+
+        std::fs::write(&path, "recreated\n").unwrap();
+        source.refresh().unwrap();
+        assert_eq!(source.poll_line().unwrap(), Some("recreated".to_string()));
+    }
+
+    #[test]
+    fn follow_set_waits_for_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deploy.log");
+        let mut set = FollowSet::new(vec![FollowSource::waiting(&path)]);
+
+        let writer_path = path.clone();
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            std::fs::write(writer_path, "started\n").unwrap();
+        });
+        assert_eq!(set.next_line().unwrap(), Some((0, "started".to_string())));
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn follow_set_empty() {
+        let mut set = FollowSet::new(vec![]);
+        assert_eq!(set.next_line().unwrap(), None);
+    }
+
     #[test]
     fn follow_source_nonexistent_file() {
         let result = FollowSource::new(Path::new("/nonexistent/path/file.txt"));
@@ -23,6 +23,44 @@ impl Level {
             _ => None,
         }
     }
+
+    /// Map a Go `log/slog` level with an offset, such as `INFO+2` or `DEBUG-4`,
+    /// onto the nearest level. slog's levels are 4 apart (DEBUG -4, INFO 0,
+    /// WARN 4, ERROR 8), with TRACE at -8 and FATAL at 12 by convention; ties
+    /// round down.
+    pub fn from_slog_offset(s: &str) -> Option<Level> {
+        const SCALE: [Level; 6] = [
+            Level::Trace,
+            Level::Debug,
+            Level::Info,
+            Level::Warn,
+            Level::Error,
+            Level::Fatal,
+        ];
+        let (name, offset) = s.split_at(s.find(['+', '-'])?);
+        let offset: i64 = offset.parse().ok()?;
+        let base: i64 = match name.to_ascii_uppercase().as_str() {
+            "DEBUG" => -4,
+            "INFO" => 0,
+            "WARN" => 4,
+            "ERROR" => 8,
+            _ => return None,
+        };
+        let index = base.saturating_add(offset).saturating_add(9).div_euclid(4);
+        Some(SCALE[index.clamp(0, 5) as usize])
+    }
+
+    /// Map a syslog severity (0 = emergency to 7 = debug), as used by journald.
+    pub fn from_syslog_priority(n: i64) -> Option<Level> {
+        match n {
+            0..=2 => Some(Level::Fatal),
+            3 => Some(Level::Error),
+            4 => Some(Level::Warn),
+            5 | 6 => Some(Level::Info),
+            7 => Some(Level::Debug),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Level {
@@ -46,10 +84,10 @@ impl FromStr for Level {
         match s.to_ascii_uppercase().as_str() {
             "TRACE" => Ok(Level::Trace),
             "DEBUG" => Ok(Level::Debug),
-            "INFO" => Ok(Level::Info),
+            "INFO" | "NOTICE" => Ok(Level::Info),
             "WARN" | "WARNING" => Ok(Level::Warn),
-            "ERROR" => Ok(Level::Error),
-            "FATAL" | "CRITICAL" | "PANIC" => Ok(Level::Fatal),
+            "ERROR" | "DPANIC" => Ok(Level::Error),
+            "FATAL" | "CRITICAL" | "PANIC" | "ALERT" | "EMERGENCY" => Ok(Level::Fatal),
             _ => Err(format!("unknown log level: {s}")),
         }
     }
@@ -92,6 +130,10 @@ mod tests {
         assert_eq!("warning".parse::<Level>().unwrap(), Level::Warn);
         assert_eq!("CRITICAL".parse::<Level>().unwrap(), Level::Fatal);
         assert_eq!("PANIC".parse::<Level>().unwrap(), Level::Fatal);
+        assert_eq!("DPANIC".parse::<Level>().unwrap(), Level::Error);
+        assert_eq!("NOTICE".parse::<Level>().unwrap(), Level::Info);
+        assert_eq!("ALERT".parse::<Level>().unwrap(), Level::Fatal);
+        assert_eq!("EMERGENCY".parse::<Level>().unwrap(), Level::Fatal);
     }
 
     #[test]
@@ -129,6 +171,33 @@ mod tests {
         assert_eq!(Level::from_bunyan_int(-1), None);
     }
 
+    #[test]
+    fn slog_offsets() {
+        assert_eq!(Level::from_slog_offset("INFO+2"), Some(Level::Info));
+        assert_eq!(Level::from_slog_offset("INFO+3"), Some(Level::Warn));
+        assert_eq!(Level::from_slog_offset("WARN-1"), Some(Level::Warn));
+        assert_eq!(Level::from_slog_offset("DEBUG-4"), Some(Level::Trace));
+        assert_eq!(Level::from_slog_offset("ERROR+4"), Some(Level::Fatal));
+        assert_eq!(Level::from_slog_offset("error+100"), Some(Level::Fatal));
+        assert_eq!(Level::from_slog_offset("DEBUG-100"), Some(Level::Trace));
+        assert_eq!(Level::from_slog_offset("INFO"), None);
+        assert_eq!(Level::from_slog_offset("NOTICE+1"), None);
+        assert_eq!(Level::from_slog_offset("INFO+x"), None);
+    }
+
+    #[test]
+    fn syslog_priorities() {
+        assert_eq!(Level::from_syslog_priority(0), Some(Level::Fatal));
+        assert_eq!(Level::from_syslog_priority(2), Some(Level::Fatal));
+        assert_eq!(Level::from_syslog_priority(3), Some(Level::Error));
+        assert_eq!(Level::from_syslog_priority(4), Some(Level::Warn));
+        assert_eq!(Level::from_syslog_priority(5), Some(Level::Info));
+        assert_eq!(Level::from_syslog_priority(6), Some(Level::Info));
+        assert_eq!(Level::from_syslog_priority(7), Some(Level::Debug));
+        assert_eq!(Level::from_syslog_priority(8), None);
+        assert_eq!(Level::from_syslog_priority(-1), None);
+    }
+
     #[test]
     fn ordering() {
         assert!(Level::Trace < Level::Debug);
@@ -1,12 +1,23 @@
 pub mod cli;
 pub mod color;
+pub mod container;
+pub mod context;
+pub mod decompress;
 pub mod error;
+pub mod expand;
+pub mod filter;
 pub mod format;
 pub mod input;
 pub mod level;
+pub mod line_format;
+pub mod logfmt;
+pub mod message_template;
+pub mod multiline;
 pub mod output;
 pub mod parse;
 pub mod pipeline;
 pub mod record;
 pub mod schema;
+pub mod search;
 pub mod timestamp;
+pub mod watch;
@@ -0,0 +1,265 @@
+use chrono::DateTime;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::cli::LinePreset;
+use crate::error::JlError;
+use crate::schema::{FieldMapping, LevelFormat, TimestampFormat};
+
+/// The Common Log Format: `127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /a.gif HTTP/1.0" 200 2326`.
+/// Quoted fields allow backslash escapes, as written by both Apache and nginx.
+const CLF: &str = r#"^(?P<remote_addr>\S+) \S+ (?P<remote_user>\S+) \[(?P<time>[^\]]+)\] "(?P<request>(?:[^"\\]|\\.)*)" (?P<status>\d{3}) (?P<bytes>\d+|-)"#;
+
+/// What the Combined Log Format adds to [`CLF`].
+const COMBINED_TAIL: &str = r#" "(?P<referer>(?:[^"\\]|\\.)*)" "(?P<user_agent>(?:[^"\\]|\\.)*)""#;
+
+/// What the `main` format in nginx's stock configuration adds to the combined one.
+const NGINX_TAIL: &str = r#"(?: "(?P<forwarded_for>(?:[^"\\]|\\.)*)")?"#;
+
+/// A format for plain-text log lines: a regex whose named captures become the
+/// fields of a record.
+///
+/// With `--line-regex`, captures named after a canonical role (`timestamp`,
+/// `level`, `logger`, `message` or `stack_trace`) fill that role; any other
+/// named capture, such as `thread`, becomes an extra field. The access log
+/// presets of `--line-format` also derive the level from the status code and
+/// split the request line into its method and path.
+#[derive(Debug)]
+pub struct LineFormat {
+    regex: Regex,
+    access_log: bool,
+}
+
+impl LineFormat {
+    /// Compile a `--line-regex` pattern, which must have at least one named capture.
+    pub fn compile(pattern: &str) -> Result<Self, JlError> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| JlError::Parse(format!("invalid --line-regex: {e}")))?;
+        if regex.capture_names().flatten().next().is_none() {
+            return Err(JlError::Parse(
+                "--line-regex needs named captures, e.g. (?P<message>.*)".to_string(),
+            ));
+        }
+        Ok(Self {
+            regex,
+            access_log: false,
+        })
+    }
+
+    /// The format for a built-in `--line-format` preset.
+    pub fn preset(preset: LinePreset) -> Self {
+        let pattern = match preset {
+            LinePreset::Clf => format!("{CLF}$"),
+            LinePreset::Combined => format!("{CLF}{COMBINED_TAIL}$"),
+            LinePreset::Nginx => format!("{CLF}{COMBINED_TAIL}{NGINX_TAIL}$"),
+        };
+        Self {
+            regex: Regex::new(&pattern).expect("preset patterns are valid"),
+            access_log: true,
+        }
+    }
+
+    /// Match a line, returning its named captures as a JSON object of
+    /// strings. Captures that did not take part in the match are left out.
+    pub fn parse(&self, line: &str) -> Option<Value> {
+        let caps = self.regex.captures(line)?;
+        let fields: Map<String, Value> = self
+            .regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| {
+                let m = caps.name(name)?;
+                Some((name.to_string(), Value::String(m.as_str().to_string())))
+            })
+            .collect();
+        if self.access_log {
+            return Some(Value::Object(access_record(fields)));
+        }
+        Some(Value::Object(fields))
+    }
+
+    /// The field mapping for records built from matched lines, whose keys are
+    /// the canonical role names themselves.
+    pub fn field_mapping() -> FieldMapping {
+        FieldMapping {
+            level: vec!["level"],
+            timestamp: vec!["timestamp"],
+            logger: vec!["logger"],
+            source_location: vec![],
+            message: vec!["message"],
+            stack_trace: vec!["stack_trace"],
+            level_format: LevelFormat::Standard,
+            timestamp_format: TimestampFormat::Standard,
+            payload: None,
+            template: None,
+        }
+    }
+}
+
+/// Turn the captures of an access log line into a record: the request line
+/// becomes the message, the status sets the level (5xx is `ERROR`, 4xx is
+/// `WARN`), and `-` placeholders for absent values are dropped.
+fn access_record(captures: Map<String, Value>) -> Map<String, Value> {
+    let mut record = Map::new();
+    for (key, value) in captures {
+        let Value::String(text) = value else {
+            continue;
+        };
+        if text == "-" {
+            continue;
+        }
+        match key.as_str() {
+            "time" => {
+                // `10/Oct/2000:13:55:36 -0700`; kept as written if it does not parse
+                let timestamp = DateTime::parse_from_str(&text, "%d/%b/%Y:%H:%M:%S %z")
+                    .map(|dt| dt.to_rfc3339())
+                    .unwrap_or(text);
+                record.insert("timestamp".to_string(), Value::String(timestamp));
+            }
+            "request" => {
+                let mut parts = text.split(' ');
+                if let (Some(method), Some(path)) = (parts.next(), parts.next()) {
+                    record.insert("method".to_string(), Value::String(method.to_string()));
+                    record.insert("path".to_string(), Value::String(path.to_string()));
+                }
+                record.insert("message".to_string(), Value::String(text));
+            }
+            "status" | "bytes" => {
+                let n: u64 = text.parse().unwrap_or_default();
+                if key == "status" {
+                    let level = match n {
+                        500.. => "ERROR",
+                        400..500 => "WARN",
+                        _ => "INFO",
+                    };
+                    record.insert("level".to_string(), Value::String(level.to_string()));
+                }
+                record.insert(key, Value::from(n));
+            }
+            _ => {
+                record.insert(key, Value::String(text));
+            }
+        }
+    }
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const LOG4J: &str = r"(?P<timestamp>\S+ \S+) \[(?P<thread>[^]]+)\] (?P<level>\w+)\s+(?P<logger>\S+) - (?P<message>.*)";
+
+    #[test]
+    fn captures_become_fields() {
+        let format = LineFormat::compile(LOG4J).unwrap();
+        let value = format
+            .parse("2024-01-15 10:30:00,123 [main] INFO  com.foo.Bar - Server started")
+            .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "timestamp": "2024-01-15 10:30:00,123",
+                "thread": "main",
+                "level": "INFO",
+                "logger": "com.foo.Bar",
+                "message": "Server started"
+            })
+        );
+    }
+
+    #[test]
+    fn unmatched_line_is_none() {
+        let format = LineFormat::compile(LOG4J).unwrap();
+        assert_eq!(format.parse("\tat com.foo.Bar.run(Bar.java:42)"), None);
+    }
+
+    #[test]
+    fn optional_captures_left_out() {
+        let format =
+            LineFormat::compile(r"^(?P<level>[A-Z]+)(?: \[(?P<logger>\w+)\])? (?P<message>.*)$")
+                .unwrap();
+        assert_eq!(
+            format.parse("WARN low disk").unwrap(),
+            json!({"level": "WARN", "message": "low disk"})
+        );
+    }
+
+    #[test]
+    fn clf_line() {
+        let format = LineFormat::preset(LinePreset::Clf);
+        let value = format
+            .parse(r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#)
+            .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "remote_addr": "127.0.0.1",
+                "remote_user": "frank",
+                "timestamp": "2000-10-10T13:55:36-07:00",
+                "message": "GET /apache_pb.gif HTTP/1.0",
+                "method": "GET",
+                "path": "/apache_pb.gif",
+                "level": "INFO",
+                "status": 200,
+                "bytes": 2326
+            })
+        );
+    }
+
+    #[test]
+    fn combined_line_with_error_status() {
+        let format = LineFormat::preset(LinePreset::Combined);
+        let value = format
+            .parse(r#"10.0.0.5 - - [15/Jan/2024:10:30:00 +0000] "POST /api/orders HTTP/1.1" 503 - "https://shop.example/" "curl/8.5.0""#)
+            .unwrap();
+        assert_eq!(value["level"], "ERROR");
+        assert_eq!(value["status"], 503);
+        assert_eq!(value["referer"], "https://shop.example/");
+        assert_eq!(value["user_agent"], "curl/8.5.0");
+        assert!(value.get("bytes").is_none());
+        assert!(value.get("remote_user").is_none());
+    }
+
+    #[test]
+    fn nginx_line_with_forwarded_for() {
+        let format = LineFormat::preset(LinePreset::Nginx);
+        let line = r#"10.0.0.5 - - [15/Jan/2024:10:30:00 +0000] "GET /missing HTTP/1.1" 404 153 "-" "Mozilla/5.0 (X11; Linux)""#;
+        let value = format.parse(line).unwrap();
+        assert_eq!(value["level"], "WARN");
+        assert!(value.get("referer").is_none());
+        assert_eq!(value["user_agent"], "Mozilla/5.0 (X11; Linux)");
+
+        let value = format.parse(&format!(r#"{line} "203.0.113.7""#)).unwrap();
+        assert_eq!(value["forwarded_for"], "203.0.113.7");
+    }
+
+    #[test]
+    fn malformed_request_line_kept_as_message() {
+        let format = LineFormat::preset(LinePreset::Clf);
+        let value = format
+            .parse(r#"10.0.0.5 - - [15/Jan/2024:10:30:00 +0000] "\x16\x03\x01" 400 0"#)
+            .unwrap();
+        assert_eq!(value["message"], r"\x16\x03\x01");
+        assert!(value.get("method").is_none());
+    }
+
+    #[test]
+    fn presets_reject_other_lines() {
+        let format = LineFormat::preset(LinePreset::Combined);
+        assert_eq!(
+            format.parse(r#"127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] "GET / HTTP/1.0" 200 2326"#),
+            None
+        );
+        assert_eq!(format.parse("Server started"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_or_unnamed_patterns() {
+        let err = LineFormat::compile("(unclosed").unwrap_err();
+        assert!(format!("{err}").contains("invalid --line-regex"));
+        let err = LineFormat::compile(r"(\w+) (.*)").unwrap_err();
+        assert!(format!("{err}").contains("named captures"));
+    }
+}
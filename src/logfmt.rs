@@ -0,0 +1,176 @@
+use serde_json::{Map, Number, Value};
+
+/// Keys that mark a lone `key=value` pair as a log line rather than text that
+/// happens to contain `=`, such as `PATH=/usr/bin`.
+const LOG_KEYS: [&str; 6] = ["level", "lvl", "msg", "message", "time", "ts"];
+
+/// Parse a logfmt line (`ts=2024-01-15T10:30:00Z level=info msg="started" port=8080`)
+/// into a JSON object, so it can go through the same schema detection and
+/// extraction as JSON input.
+///
+/// Every space-separated token must be a `key=value` pair; lines with bare
+/// words are not logfmt and yield `None`, as are lines with a single pair
+/// whose key is not a level, message or time key. Quoted values may contain
+/// spaces and the escapes `\"`, `\\`, `\n`, `\r` and `\t`, and are kept as
+/// strings. Unquoted numbers that read back exactly as written and
+/// `true`/`false` become numbers and booleans, so they compare naturally in
+/// `--where` filters; other values, such as `1e3`, stay strings.
+pub fn parse(line: &str) -> Option<Map<String, Value>> {
+    let mut rest = line.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut map = Map::new();
+    while !rest.is_empty() {
+        let key_end = rest.find(|c: char| c == '=' || c.is_whitespace())?;
+        let key = &rest[..key_end];
+        if key.is_empty() || key.contains('"') || !rest[key_end..].starts_with('=') {
+            return None;
+        }
+        rest = &rest[key_end + 1..];
+        let value = match rest.strip_prefix('"') {
+            Some(quoted) => {
+                let (value, after) = parse_quoted(quoted)?;
+                rest = after;
+                Value::String(value)
+            }
+            None => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let raw = &rest[..end];
+                if raw.contains('"') {
+                    return None;
+                }
+                rest = &rest[end..];
+                unquoted_value(raw)
+            }
+        };
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        rest = rest.trim_start();
+        map.insert(key.to_string(), value);
+    }
+    let is_log = map.len() >= 2 || map.keys().any(|k| LOG_KEYS.contains(&k.as_str()));
+    is_log.then_some(map)
+}
+
+/// Read a quoted value up to its closing quote, returning the unescaped value
+/// and the text after the quote.
+fn parse_quoted(s: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &s[i + 1..])),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                escaped @ ('"' | '\\') => value.push(escaped),
+                other => {
+                    value.push('\\');
+                    value.push(other);
+                }
+            },
+            _ => value.push(c),
+        }
+    }
+    None
+}
+
+fn unquoted_value(raw: &str) -> Value {
+    match raw {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => match serde_json::from_str::<Number>(raw) {
+            Ok(n) if n.to_string() == raw => Value::Number(n),
+            _ => Value::String(raw.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_value(line: &str) -> Option<Value> {
+        parse(line).map(Value::Object)
+    }
+
+    #[test]
+    fn parses_pairs() {
+        assert_eq!(
+            parse_value(r#"ts=2024-01-15T10:30:00Z level=info msg="server started" port=8080"#),
+            Some(json!({
+                "ts": "2024-01-15T10:30:00Z",
+                "level": "info",
+                "msg": "server started",
+                "port": 8080
+            }))
+        );
+    }
+
+    #[test]
+    fn typed_unquoted_values() {
+        assert_eq!(
+            parse_value(r#"a=1.5 b=-3 c=true d=false e=007 f="42" g= h=1e3 i=2.50"#),
+            Some(json!({
+                "a": 1.5,
+                "b": -3,
+                "c": true,
+                "d": false,
+                "e": "007",
+                "f": "42",
+                "g": "",
+                "h": "1e3",
+                "i": "2.50"
+            }))
+        );
+    }
+
+    #[test]
+    fn quoted_escapes() {
+        assert_eq!(
+            parse_value(r#"msg="say \"hi\"\n\tnow" path="C:\\logs" other="a\qb""#),
+            Some(json!({
+                "msg": "say \"hi\"\n\tnow",
+                "path": "C:\\logs",
+                "other": "a\\qb"
+            }))
+        );
+    }
+
+    #[test]
+    fn extra_whitespace_between_pairs() {
+        assert_eq!(
+            parse_value("  level=warn \t msg=slow  "),
+            Some(json!({"level": "warn", "msg": "slow"}))
+        );
+    }
+
+    #[test]
+    fn rejects_plain_text() {
+        for line in [
+            "",
+            "   ",
+            "Server started on port 8080",
+            "2024-01-15 10:30:00 level=info",
+            "level=info started",
+            "=value",
+            r#"msg="unterminated"#,
+            r#"msg="a"b"#,
+            r#"msg=a"b"#,
+            "PATH=/usr/bin",
+            "a=b",
+        ] {
+            assert_eq!(parse(line), None, "{line:?}");
+        }
+    }
+
+    #[test]
+    fn single_pair_with_log_key() {
+        assert_eq!(parse_value("msg=started"), Some(json!({"msg": "started"})));
+        assert_eq!(parse_value("level=warn"), Some(json!({"level": "warn"})));
+    }
+}
@@ -1,15 +1,26 @@
 mod cli;
 mod color;
+mod container;
+mod context;
+mod decompress;
 mod error;
+mod expand;
+mod filter;
 mod format;
 mod input;
 mod level;
+mod line_format;
+mod logfmt;
+mod message_template;
+mod multiline;
 mod output;
 mod parse;
 mod pipeline;
 mod record;
 mod schema;
+mod search;
 mod timestamp;
+mod watch;
 
 use clap::{CommandFactory, Parser};
 
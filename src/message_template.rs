@@ -0,0 +1,111 @@
+use serde_json::{Map, Value};
+
+/// Render a message template (`"User {UserId} logged in"`), as written to
+/// CLEF's `@mt`, by substituting the record's fields for its holes.
+///
+/// Holes may carry Serilog's `@`/`$` capturing hints, a `,alignment` and a
+/// `:format`; these are ignored and the value is written as-is, strings
+/// without quotes. `{{` and `}}` are literal braces. Holes whose property is
+/// missing are kept as written.
+pub fn render(template: &str, fields: &Map<String, Value>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if let Some(after) = tail.strip_prefix("{{").or_else(|| tail.strip_prefix("}}")) {
+            out.push_str(&tail[..1]);
+            rest = after;
+            continue;
+        }
+        let end = match tail.find('}') {
+            Some(end) if tail.starts_with('{') => end,
+            _ => {
+                // A lone `}`, or a `{` that is never closed
+                out.push_str(&tail[..1]);
+                rest = &tail[1..];
+                continue;
+            }
+        };
+        match property(&tail[1..end], fields) {
+            Some(value) => {
+                out.push_str(&value);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                // Not a hole, or a missing property: keep the text, but let a
+                // hole later on (`{a {B}`) still be found
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The rendered value of the property named by a hole's contents.
+fn property(hole: &str, fields: &Map<String, Value>) -> Option<String> {
+    let hole = hole.strip_prefix(['@', '$']).unwrap_or(hole);
+    let name = hole.split([',', ':']).next()?;
+    let is_name = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !is_name {
+        return None;
+    }
+    Some(match fields.get(name)? {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render_with(template: &str, fields: Value) -> String {
+        render(template, fields.as_object().unwrap())
+    }
+
+    #[test]
+    fn substitutes_named_properties() {
+        assert_eq!(
+            render_with(
+                "User {UserId} logged in from {Ip}",
+                json!({"UserId": "alice", "Ip": "10.0.0.5"})
+            ),
+            "User alice logged in from 10.0.0.5"
+        );
+    }
+
+    #[test]
+    fn renders_non_string_values_as_json() {
+        assert_eq!(
+            render_with(
+                "Processed {@Order} in {Elapsed} ms, ok={Ok}",
+                json!({"Order": {"Id": 7}, "Elapsed": 34.5, "Ok": true})
+            ),
+            r#"Processed {"Id":7} in 34.5 ms, ok=true"#
+        );
+    }
+
+    #[test]
+    fn ignores_format_and_alignment() {
+        assert_eq!(
+            render_with(
+                "{$Name,-10} took {Elapsed:0.00} ({0})",
+                json!({"Name": "job", "Elapsed": 1.5, "0": "first"})
+            ),
+            "job took 1.5 (first)"
+        );
+    }
+
+    #[test]
+    fn escaped_braces_and_missing_properties() {
+        assert_eq!(
+            render_with("{{literal}} {Missing} {not a hole} }{", json!({})),
+            "{literal} {Missing} {not a hole} }{"
+        );
+        assert_eq!(render_with("{a {B}", json!({"B": 2})), "{a 2");
+    }
+}
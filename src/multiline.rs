@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+use std::mem;
+
+use serde::de::IgnoredAny;
+
+/// How much text a JSON value may span before it is assumed to be malformed
+/// and its lines are passed through one by one.
+const MAX_PENDING_BYTES: usize = 1 << 20;
+
+/// Joins JSON objects that span several input lines, such as pretty-printed
+/// output, into one logical line each.
+///
+/// Braces and brackets are counted outside of string literals; an object is
+/// complete once they balance. Several objects concatenated on one line are
+/// split apart, while lines that do not start an object pass straight
+/// through. If the joined text does not parse as JSON, its original lines are
+/// passed through unchanged instead.
+///
+/// An object that never closes is given up on when another object starts in
+/// the first column, when it grows past [`MAX_PENDING_BYTES`], or at the end
+/// of input.
+#[derive(Debug, Default)]
+pub struct JsonJoiner {
+    /// The lines, or line fragments, of the object being collected.
+    pieces: Vec<String>,
+    pending_bytes: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonJoiner {
+    /// Feed one input line, appending every logical line it completes to `out`.
+    pub fn push(&mut self, line: String, out: &mut VecDeque<String>) {
+        if self.depth == 0 {
+            if !line.trim_start().starts_with('{') {
+                out.push_back(line);
+                return;
+            }
+        } else if line.starts_with('{') {
+            // An unindented object while one is still open: the open one was
+            // truncated, so let it go and start over
+            self.give_up(out);
+        }
+
+        let mut start = 0;
+        for (i, b) in line.bytes().enumerate() {
+            if self.in_string {
+                match b {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match b {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' if self.depth > 0 => {
+                    self.depth -= 1;
+                    if self.depth > 0 {
+                        continue;
+                    }
+                    // Another object may follow on the same line; anything else
+                    // after the closing brace stays with this one
+                    let end = i + 1;
+                    if !line[end..].trim_start().starts_with('{') {
+                        self.add_piece(&line[start..]);
+                        self.complete(out);
+                        return;
+                    }
+                    self.add_piece(&line[start..end]);
+                    self.complete(out);
+                    start = end;
+                }
+                _ => {}
+            }
+        }
+        self.add_piece(&line[start..]);
+        if self.pending_bytes > MAX_PENDING_BYTES {
+            self.give_up(out);
+        }
+    }
+
+    /// At the end of input, pass through the lines of an object that never
+    /// closed. Returns whether any were added to `out`.
+    pub fn finish(&mut self, out: &mut VecDeque<String>) -> bool {
+        let pending = !self.pieces.is_empty();
+        self.give_up(out);
+        pending
+    }
+
+    fn add_piece(&mut self, piece: &str) {
+        self.pending_bytes += piece.len();
+        self.pieces.push(piece.to_string());
+    }
+
+    /// Emit the balanced object collected so far as one logical line.
+    fn complete(&mut self, out: &mut VecDeque<String>) {
+        self.pending_bytes = 0;
+        let mut pieces = mem::take(&mut self.pieces);
+        if pieces.len() == 1 {
+            out.extend(pieces.pop());
+            return;
+        }
+        let joined = pieces.join("\n");
+        if serde_json::from_str::<IgnoredAny>(&joined).is_ok() {
+            out.push_back(joined);
+        } else {
+            out.extend(pieces);
+        }
+    }
+
+    /// Pass the collected lines through unjoined and reset.
+    fn give_up(&mut self, out: &mut VecDeque<String>) {
+        out.extend(self.pieces.drain(..));
+        self.pending_bytes = 0;
+        self.depth = 0;
+        self.in_string = false;
+        self.escaped = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn join(lines: &[&str]) -> Vec<String> {
+        let mut joiner = JsonJoiner::default();
+        let mut out = VecDeque::new();
+        for line in lines {
+            joiner.push(line.to_string(), &mut out);
+        }
+        joiner.finish(&mut out);
+        out.into()
+    }
+
+    #[test]
+    fn single_line_values_pass_through() {
+        let lines = [r#"{"msg":"a"}"#, "plain text", r#"  {"msg":"b"}"#, ""];
+        assert_eq!(join(&lines), lines);
+    }
+
+    #[test]
+    fn joins_pretty_printed_object() {
+        let out = join(&[
+            "{",
+            r#"  "level": "INFO","#,
+            r#"  "ctx": {"user": "alice", "tags": ["a", "b"]},"#,
+            r#"  "msg": "braces } and \" in strings {""#,
+            "}",
+            "after",
+        ]);
+        assert_eq!(out.len(), 2);
+        let value: serde_json::Value = serde_json::from_str(&out[0]).unwrap();
+        assert_eq!(value["msg"], r#"braces } and " in strings {"#);
+        assert_eq!(value["ctx"]["user"], "alice");
+        assert_eq!(out[1], "after");
+    }
+
+    #[test]
+    fn splits_concatenated_objects() {
+        let out = join(&[r#"{"a":1}{"b":2} {"#, r#""c":3}"#]);
+        assert_eq!(out, [r#"{"a":1}"#, r#"{"b":2}"#, " {\n\"c\":3}"]);
+    }
+
+    #[test]
+    fn trailing_text_stays_with_its_line() {
+        assert_eq!(join(&[r#"{"a":1} done"#]), [r#"{"a":1} done"#]);
+    }
+
+    #[test]
+    fn invalid_joined_text_passes_lines_through() {
+        let out = join(&["{", "  not json", "}"]);
+        assert_eq!(out, ["{", "  not json", "}"]);
+    }
+
+    #[test]
+    fn unclosed_object_flushed_at_end() {
+        let out = join(&[r#"{"level":"INFO""#, "  more"]);
+        assert_eq!(out, [r#"{"level":"INFO""#, "  more"]);
+    }
+
+    #[test]
+    fn unindented_object_abandons_truncated_one() {
+        let out = join(&[r#"{"msg":"cut"#, r#"{"msg":"next"}"#]);
+        assert_eq!(out, [r#"{"msg":"cut"#, r#"{"msg":"next"}"#]);
+    }
+}
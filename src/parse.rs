@@ -3,12 +3,18 @@ use serde_json::Value;
 use crate::cli::NonJsonMode;
 use crate::error::JlError;
 use crate::format::sanitize_control_chars;
+use crate::logfmt;
 
 /// The result of parsing a single input line.
 #[derive(Debug)]
 pub enum ParseResult {
-    /// The line was valid JSON.
+    /// The line was valid JSON, or logfmt converted to a JSON object.
     Json(Value),
+    /// A JSON object found after a plain-text prefix on the line.
+    Prefixed { prefix: String, value: Value },
+    /// A plain-text line matched by `--line-regex`, with its named captures
+    /// as an object.
+    Matched(Value),
     /// The line was not valid JSON and should be printed through (with sanitization).
     NonJson(String),
     /// The line was not valid JSON and should be skipped.
@@ -18,24 +24,84 @@ pub enum ParseResult {
 /// Parse a single input line, handling non-JSON lines according to `mode`.
 ///
 /// - Valid JSON always returns `ParseResult::Json(value)`.
-/// - Invalid JSON behavior depends on `mode`:
+/// - A logfmt line (`level=info msg="started"`) returns `ParseResult::Json`
+///   with the pairs as an object.
+/// - Other lines depend on `mode`:
 ///   - `PrintAsIs` -> `ParseResult::NonJson(line)` (caller sanitizes before output)
 ///   - `Skip` -> `ParseResult::Skip`
 ///   - `Fail` -> `Err(JlError::Parse(...))`
 pub fn parse_line(line: &str, mode: NonJsonMode) -> Result<ParseResult, JlError> {
     match serde_json::from_str::<Value>(line) {
         Ok(value) => Ok(ParseResult::Json(value)),
-        Err(_) => match mode {
-            NonJsonMode::PrintAsIs => Ok(ParseResult::NonJson(line.to_string())),
-            NonJsonMode::Skip => Ok(ParseResult::Skip),
-            NonJsonMode::Fail => Err(JlError::Parse(format!(
-                "not valid JSON: {}",
-                sanitize_control_chars(line)
-            ))),
+        Err(_) => match logfmt::parse(line) {
+            Some(pairs) => Ok(ParseResult::Json(Value::Object(pairs))),
+            None => non_json(line, mode),
         },
     }
 }
 
+/// Parse a line that may carry plain text before its JSON, such as
+/// `kubectl logs --prefix` output or a syslog header.
+///
+/// Lines that are JSON from the start parse as with [`parse_line`]. Otherwise
+/// the first `{` that starts an object running to the end of the line is
+/// used, returning `ParseResult::Prefixed` with the text before it (trailing
+/// whitespace trimmed). Lines without such an object are handled like
+/// [`parse_line`] handles non-JSON lines.
+pub fn parse_prefixed_line(line: &str, mode: NonJsonMode) -> Result<ParseResult, JlError> {
+    if let Ok(value) = serde_json::from_str::<Value>(line) {
+        return Ok(ParseResult::Json(value));
+    }
+    for (start, _) in line.match_indices('{') {
+        let mut stream = serde_json::Deserializer::from_str(&line[start..]).into_iter::<Value>();
+        if let Some(Ok(value)) = stream.next()
+            && value.is_object()
+            && line[start + stream.byte_offset()..].trim().is_empty()
+        {
+            let prefix = line[..start].trim_end().to_string();
+            return Ok(ParseResult::Prefixed { prefix, value });
+        }
+    }
+    match logfmt::parse(line) {
+        Some(pairs) => Ok(ParseResult::Json(Value::Object(pairs))),
+        None => non_json(line, mode),
+    }
+}
+
+/// Whether a non-JSON line looks like part of a stack trace printed after a
+/// JSON record: an indented line, an `at ...` frame, a `Caused by:` or
+/// `Suppressed:` header, or an exception header such as
+/// `java.lang.IllegalStateException: boom`.
+pub fn is_continuation(line: &str) -> bool {
+    if line.starts_with([' ', '\t']) {
+        return !line.trim().is_empty();
+    }
+    if ["at ", "Caused by:", "Suppressed:", "... "]
+        .iter()
+        .any(|p| line.starts_with(p))
+    {
+        return true;
+    }
+    let head = line.split_once(':').map_or(line, |(head, _)| head);
+    head.contains('.')
+        && !head.contains(' ')
+        && ["Exception", "Error", "Throwable"]
+            .iter()
+            .any(|suffix| head.ends_with(suffix))
+}
+
+/// Handle a line that holds no JSON according to `mode`.
+pub fn non_json(line: &str, mode: NonJsonMode) -> Result<ParseResult, JlError> {
+    match mode {
+        NonJsonMode::PrintAsIs => Ok(ParseResult::NonJson(line.to_string())),
+        NonJsonMode::Skip => Ok(ParseResult::Skip),
+        NonJsonMode::Fail => Err(JlError::Parse(format!(
+            "not valid JSON: {}",
+            sanitize_control_chars(line)
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,6 +276,95 @@ mod tests {
         }
     }
 
+    // --- logfmt ---
+
+    #[test]
+    fn logfmt_line_becomes_json_object() {
+        let result =
+            parse_line(r#"level=info msg="started" port=8080"#, NonJsonMode::Fail).unwrap();
+        match result {
+            ParseResult::Json(v) => {
+                assert_eq!(v, json!({"level": "info", "msg": "started", "port": 8080}));
+            }
+            _ => panic!("expected Json variant"),
+        }
+    }
+
+    #[test]
+    fn logfmt_with_find_json() {
+        let result = parse_prefixed_line("level=warn msg=slow", NonJsonMode::Fail).unwrap();
+        assert!(matches!(result, ParseResult::Json(_)));
+    }
+
+    // --- JSON after a plain-text prefix ---
+
+    fn prefixed(line: &str) -> (String, Value) {
+        match parse_prefixed_line(line, NonJsonMode::Fail).unwrap() {
+            ParseResult::Prefixed { prefix, value } => (prefix, value),
+            other => panic!("expected Prefixed variant, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn prefixed_kubectl_line() {
+        let (prefix, value) = prefixed(r#"[pod/web-7d4/app] {"level":"INFO","msg":"ready"}"#);
+        assert_eq!(prefix, "[pod/web-7d4/app]");
+        assert_eq!(value["msg"], json!("ready"));
+    }
+
+    #[test]
+    fn prefixed_skips_braces_that_do_not_start_an_object() {
+        let (prefix, value) = prefixed(r#"Jan 15 10:30:00 host app[{pid}]: {"msg":"hi"}  "#);
+        assert_eq!(prefix, "Jan 15 10:30:00 host app[{pid}]:");
+        assert_eq!(value, json!({"msg": "hi"}));
+    }
+
+    #[test]
+    fn prefixed_requires_object_to_end_the_line() {
+        let result =
+            parse_prefixed_line(r#"note {"a":1} trailing"#, NonJsonMode::PrintAsIs).unwrap();
+        assert!(matches!(result, ParseResult::NonJson(_)));
+        let result = parse_prefixed_line("count [1, 2]", NonJsonMode::Skip).unwrap();
+        assert!(matches!(result, ParseResult::Skip));
+    }
+
+    #[test]
+    fn prefixed_plain_json_unchanged() {
+        let result = parse_prefixed_line(r#"{"msg":"hi"}"#, NonJsonMode::Fail).unwrap();
+        assert!(matches!(result, ParseResult::Json(_)));
+    }
+
+    // --- Stack trace continuation lines ---
+
+    #[test]
+    fn continuation_lines() {
+        for line in [
+            "\tat com.example.App.run(App.java:42)",
+            "    at com.example.App.main(App.java:10)",
+            "at com.example.App.run(App.java:42)",
+            "Caused by: java.io.IOException: disk full",
+            "\t... 12 more",
+            "java.lang.IllegalStateException: boom",
+            "com.example.CustomError",
+        ] {
+            assert!(is_continuation(line), "{line:?}");
+        }
+    }
+
+    #[test]
+    fn non_continuation_lines() {
+        for line in [
+            "",
+            "   ",
+            "Server started on port 8080",
+            "attention: disk almost full",
+            "Exception: not a qualified class name",
+            "error in main.rs: something",
+        ] {
+            assert!(!is_continuation(line), "{line:?}");
+        }
+    }
+
     #[test]
     fn json_boolean_values() {
         let result = parse_line("true", NonJsonMode::PrintAsIs).unwrap();
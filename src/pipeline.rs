@@ -1,24 +1,31 @@
-use crate::cli::Args;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset, Utc};
+
+use crate::cli::{Args, NonJsonMode};
 use crate::color::ColorConfig;
+use crate::container::{Envelope, Reassembler};
+use crate::context::ContextBuffer;
 use crate::error::JlError;
+use crate::expand;
+use crate::filter::Filter;
 use crate::format;
-use crate::input::{FileSource, FollowSource, LineSource, StdinSource};
+use crate::input::{FileSource, FollowSet, FollowSource, LineSource, StdinSource};
+use crate::line_format::LineFormat;
+use crate::multiline::JsonJoiner;
 use crate::output::{FileSink, OutputSink, StdoutSink};
 use crate::parse::{self, ParseResult};
 use crate::record::LogRecord;
-use crate::schema::Schema;
+use crate::schema::{FieldMapping, Schema};
+use crate::search::Search;
+use crate::timestamp;
 
 /// Run the full pipeline: read lines, parse, extract, filter, render, write.
 pub fn run(args: Args) -> Result<(), JlError> {
-    // When writing to a file, disable auto-color since the output is not a terminal
-    let color = match (&args.output, args.color) {
-        (Some(_), crate::cli::ColorMode::Auto) => {
-            ColorConfig::new(crate::cli::ColorMode::Never, args.key_color, args.value_color)
-        }
-        _ => ColorConfig::new(args.color, args.key_color, args.value_color),
-    };
-    let tokens = format::parse_template(&args.format);
-    let render_ctx = format::RenderContext::new(&args, &tokens);
+    let mut processor = Processor::new(&args)?;
 
     let mut output: Box<dyn OutputSink> = match &args.output {
         Some(path) => Box::new(FileSink::new(path)?),
@@ -32,49 +39,34 @@ pub fn run(args: Args) -> Result<(), JlError> {
             ));
         }
         let mut source = StdinSource::new();
-        process_source(
-            &mut source,
-            &mut *output,
-            &tokens,
-            &color,
-            &args,
-            &render_ctx,
-        )?;
+        processor.process_source(&mut source, SourceState::new(None, &args), &mut *output)?;
     } else if args.follow {
-        // Follow mode: tail the last file, reading existing content then waiting for new lines
-        // Process any preceding files normally first
-        for path in &args.files[..args.files.len().saturating_sub(1)] {
-            let mut source = FileSource::new(path)?;
-            process_source(
-                &mut source,
-                &mut *output,
-                &tokens,
-                &color,
-                &args,
-                &render_ctx,
-            )?;
-        }
-        if let Some(path) = args.files.last() {
-            let mut source = FollowSource::new(path)?;
-            process_source(
-                &mut source,
-                &mut *output,
-                &tokens,
-                &color,
-                &args,
-                &render_ctx,
-            )?;
-        }
+        // Follow mode: read existing content of every file (or its last
+        // `--lines`), then wait for new lines
+        let files = expand::expand_inputs(&args.files, true)?;
+        processor.follow_files(&files, &mut *output)?;
+    } else if args.merge {
+        let files = expand::expand_inputs(&args.files, false)?;
+        let inputs = files
+            .iter()
+            .map(|path| {
+                Ok(MergeInput {
+                    source: processor.open_file(path)?,
+                    state: SourceState::new(Some(path), &args),
+                    head: None,
+                    key: None,
+                })
+            })
+            .collect::<Result<Vec<_>, JlError>>()?;
+        processor.merge_sources(inputs, &mut *output)?;
     } else {
-        for path in &args.files {
-            let mut source = FileSource::new(path)?;
-            process_source(
+        // Directories and globs expand to rotation sets, read oldest first
+        for path in &expand::expand_inputs(&args.files, false)? {
+            let mut source = processor.open_file(path)?;
+            processor.process_source(
                 &mut source,
+                SourceState::new(Some(path), &args),
                 &mut *output,
-                &tokens,
-                &color,
-                &args,
-                &render_ctx,
             )?;
         }
     }
@@ -83,66 +75,650 @@ pub fn run(args: Args) -> Result<(), JlError> {
     Ok(())
 }
 
-/// Process lines from a single source through the pipeline.
-fn process_source(
-    source: &mut dyn LineSource,
-    output: &mut dyn OutputSink,
-    tokens: &[format::FormatToken],
-    color: &ColorConfig,
-    args: &Args,
-    render_ctx: &format::RenderContext,
-) -> Result<(), JlError> {
-    let mut cached_schema: Option<Schema> = None;
-    let mut cached_mapping: Option<crate::schema::FieldMapping> = None;
-
-    while let Some(line) = source.next_line()? {
-        match parse::parse_line(&line, args.non_json)? {
-            ParseResult::Json(value) => {
-                // Detect or use cached schema and mapping
-                let mapping = match (&cached_schema, &cached_mapping) {
-                    (Some(_), Some(m)) => m,
-                    _ => {
-                        let s = Schema::from_choice(args.schema, &value);
-                        let m = s.field_mapping();
-                        cached_schema = Some(s);
-                        cached_mapping = Some(m);
-                        cached_mapping.as_ref().unwrap()
-                    }
-                };
+/// A decoded input line, ready to be filtered and written.
+enum Entry {
+    Record(LogRecord),
+    /// A sanitized non-JSON line.
+    Text(String),
+}
 
-                let record = LogRecord::extract(value, mapping, &args.tz, args.ts_format)?;
+/// A rendered entry after filtering.
+enum Filtered {
+    /// Passed the filters.
+    Hit(String),
+    /// Filtered out, but may be written as context around a hit.
+    Context(String),
+    /// Filtered out with no context to keep it for.
+    Dropped,
+}
 
-                // Apply --min-level filter
-                if let Some(ref min_level) = args.min_level {
-                    match &record.level {
-                        Some(level) if level < min_level => continue,
-                        _ => {}
-                    }
+impl Filtered {
+    fn write(
+        self,
+        context: &mut ContextBuffer,
+        output: &mut dyn OutputSink,
+    ) -> Result<(), JlError> {
+        match self {
+            Filtered::Hit(line) => context.hit(line, output),
+            Filtered::Context(line) => context.miss(line, output),
+            Filtered::Dropped => Ok(()),
+        }
+    }
+}
+
+/// Per-source decoding state. The schema is detected from the first JSON line
+/// of each source and reused for the rest of it.
+#[derive(Default)]
+struct SourceState {
+    mapping: Option<FieldMapping>,
+    file: Option<String>,
+    /// With `--multiline`, joins JSON objects spread over several lines.
+    joiner: Option<JsonJoiner>,
+    /// Logical lines ready to be decoded.
+    lines: VecDeque<String>,
+    /// Container log lines still waiting for their remaining chunks.
+    container: Reassembler,
+    join_continuations: bool,
+    /// With `--join-continuations`, the latest record, held back until the
+    /// next line shows whether stack trace lines follow it.
+    held: Option<LogRecord>,
+    /// Decoded entries ready to be filtered and written.
+    entries: VecDeque<Entry>,
+}
+
+impl SourceState {
+    fn new(file: Option<&Path>, args: &Args) -> Self {
+        Self {
+            file: file.map(|path| path.display().to_string()),
+            joiner: args.multiline.then(JsonJoiner::default),
+            join_continuations: args.join_continuations,
+            ..Self::default()
+        }
+    }
+
+    /// Queue a raw input line for decoding.
+    fn push_line(&mut self, line: String) {
+        match self.joiner {
+            Some(ref mut joiner) => joiner.push(line, &mut self.lines),
+            None => self.lines.push_back(line),
+        }
+    }
+
+    /// At the end of input, queue any lines still held back by the joiner.
+    /// Returns whether there were any.
+    fn finish(&mut self) -> bool {
+        self.joiner
+            .as_mut()
+            .is_some_and(|joiner| joiner.finish(&mut self.lines))
+    }
+
+    /// Queue a decoded entry to be written. With `--join-continuations`,
+    /// stack trace lines are appended to the held record instead.
+    fn push_entry(&mut self, entry: Entry) {
+        if !self.join_continuations {
+            self.entries.push_back(entry);
+            return;
+        }
+        match entry {
+            Entry::Record(record) => {
+                if let Some(previous) = self.held.replace(record) {
+                    self.entries.push_back(Entry::Record(previous));
                 }
+            }
+            Entry::Text(text) => {
+                if let Some(ref mut record) = self.held
+                    && parse::is_continuation(&text)
+                {
+                    record.append_stack_trace_line(&text);
+                } else {
+                    self.release();
+                    self.entries.push_back(Entry::Text(text));
+                }
+            }
+        }
+    }
+
+    /// Queue the held record, if any, for writing. Returns whether there was one.
+    fn release(&mut self) -> bool {
+        match self.held.take() {
+            Some(record) => {
+                self.entries.push_back(Entry::Record(record));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// One file taking part in a `--merge`, with its next entry and sort key.
+struct MergeInput {
+    source: FileSource,
+    state: SourceState,
+    head: Option<Entry>,
+    key: Option<DateTime<FixedOffset>>,
+}
+
+/// Rendering setup, compiled filters and context state shared by every source in a run.
+struct Processor<'a> {
+    args: &'a Args,
+    tokens: Vec<format::FormatToken>,
+    color: ColorConfig,
+    render_ctx: format::RenderContext,
+    where_filter: Option<Filter>,
+    line_format: Option<LineFormat>,
+    since: Option<DateTime<FixedOffset>>,
+    until: Option<DateTime<FixedOffset>>,
+    context: ContextBuffer,
+}
+
+impl<'a> Processor<'a> {
+    fn new(args: &'a Args) -> Result<Self, JlError> {
+        // When writing to a file, disable auto-color since the output is not a terminal
+        let color = match (&args.output, args.color) {
+            (Some(_), crate::cli::ColorMode::Auto) => ColorConfig::new(
+                crate::cli::ColorMode::Never,
+                args.key_color,
+                args.value_color,
+            ),
+            _ => ColorConfig::new(args.color, args.key_color, args.value_color),
+        };
+        let tokens = format::parse_template(&args.format);
+        let where_filter = args
+            .where_expr
+            .as_deref()
+            .map(Filter::compile)
+            .transpose()?;
+        let line_format = match (&args.line_regex, args.line_format) {
+            (Some(pattern), _) => Some(LineFormat::compile(pattern)?),
+            (None, Some(preset)) => Some(LineFormat::preset(preset)),
+            (None, None) => None,
+        };
+        let search = Search::from_args(args)?;
+        let now = Utc::now();
+        let since = args
+            .since
+            .as_deref()
+            .map(|s| timestamp::parse_time_bound(s, &args.tz, now))
+            .transpose()?;
+        let until = args
+            .until
+            .as_deref()
+            .map(|s| timestamp::parse_time_bound(s, &args.tz, now))
+            .transpose()?;
+        let render_ctx = format::RenderContext::new(args, &tokens).with_search(search);
+        let context = context_buffer(args);
+        Ok(Self {
+            args,
+            tokens,
+            color,
+            render_ctx,
+            where_filter,
+            line_format,
+            since,
+            until,
+            context,
+        })
+    }
+
+    /// Open a regular input file, seeking close to `--since` when it is set.
+    /// Multi-line records cannot be dated from a single line, so `--multiline`
+    /// input is read from the start, as is input shown with before-context,
+    /// which may reach back past `--since`.
+    fn open_file(&self, path: &Path) -> Result<FileSource, JlError> {
+        let mut source = FileSource::new(path)?;
+        let before = self.args.before_context.or(self.args.context).unwrap_or(0);
+        if let Some(since) = self.since
+            && !self.args.multiline
+            && before == 0
+        {
+            source.seek_to_time(since, |line| self.line_datetime(line))?;
+        }
+        Ok(source)
+    }
+
+    /// Open a file for `--follow`, honoring `--lines`, `--from-end` and `--retry`.
+    fn open_follow(&self, path: &Path) -> Result<FollowSource, JlError> {
+        let lines = if self.args.from_end {
+            Some(0)
+        } else {
+            self.args.lines
+        };
+        let opened = match lines {
+            Some(n) => FollowSource::new_from_end(path, n),
+            None => FollowSource::new(path),
+        };
+        match opened {
+            Err(JlError::Io(e)) if self.args.retry && e.kind() == io::ErrorKind::NotFound => {
+                let path_display = format::sanitize_control_chars(&path.display().to_string());
+                eprintln!("jl: {path_display} does not exist yet; waiting for it to appear");
+                Ok(FollowSource::waiting(path))
+            }
+            other => other,
+        }
+    }
 
-                let rendered = format::render(&record, tokens, color, args, render_ctx);
-                output.write_line(&rendered)?;
-                if args.follow {
+    /// Parse just enough of a line to find its timestamp, for seeking.
+    fn line_datetime(&self, line: &str) -> Option<DateTime<FixedOffset>> {
+        if let Some(envelope) = Envelope::from_cri(line) {
+            return DateTime::parse_from_rfc3339(&envelope.time).ok();
+        }
+        let (value, mapping) = match self.parse(line, NonJsonMode::Skip).ok()? {
+            ParseResult::Json(value) | ParseResult::Prefixed { value, .. } => {
+                let mapping = Schema::from_choice(self.args.schema, &value).field_mapping();
+                (value, mapping)
+            }
+            ParseResult::Matched(value) => (value, LineFormat::field_mapping()),
+            ParseResult::NonJson(_) | ParseResult::Skip => return None,
+        };
+        let obj = value.as_object()?;
+        let key = FieldMapping::find_key(&mapping.timestamp, obj)?;
+        timestamp::parse_timestamp(FieldMapping::get(obj, key)?)
+    }
+
+    /// Process lines from a single source through the pipeline.
+    fn process_source(
+        &mut self,
+        source: &mut dyn LineSource,
+        mut state: SourceState,
+        output: &mut dyn OutputSink,
+    ) -> Result<(), JlError> {
+        while let Some(entry) = self.next_entry(source, &mut state)? {
+            self.emit(entry, "", output)?;
+        }
+        Ok(())
+    }
+
+    /// Tail every input file at once, writing lines as they arrive.
+    ///
+    /// With more than one file, each line is prefixed with its file name in a
+    /// per-file color, like `tail -f` does with headers.
+    fn follow_files(
+        &mut self,
+        paths: &[PathBuf],
+        output: &mut dyn OutputSink,
+    ) -> Result<(), JlError> {
+        let sources = paths
+            .iter()
+            .map(|path| self.open_follow(path))
+            .collect::<Result<Vec<_>, JlError>>()?;
+        let mut set = FollowSet::new(sources);
+        let mut states: Vec<SourceState> = paths
+            .iter()
+            .map(|path| SourceState::new(Some(path), self.args))
+            .collect();
+        let prefixes = self.file_prefixes(paths);
+        // Each file gets its own context, so hits in one file do not pull
+        // in lines from another
+        let mut contexts: Vec<ContextBuffer> =
+            paths.iter().map(|_| context_buffer(self.args)).collect();
+        loop {
+            let next = match set.poll_line()? {
+                Some(next) => Some(next),
+                None => {
+                    // All files are idle: write records held back for
+                    // continuation lines before waiting
+                    for (i, state) in states.iter_mut().enumerate() {
+                        state.release();
+                        self.emit_queued(state, &prefixes[i], &mut contexts[i], output)?;
+                    }
                     output.flush()?;
+                    set.next_line()?
+                }
+            };
+            let Some((i, line)) = next else {
+                return Ok(());
+            };
+            let state = &mut states[i];
+            state.push_line(line);
+            while let Some(line) = state.lines.pop_front() {
+                if let Some(entry) = self.decode(&line, state)? {
+                    state.push_entry(entry);
+                }
+            }
+            self.emit_queued(state, &prefixes[i], &mut contexts[i], output)?;
+            output.flush()?;
+        }
+    }
+
+    /// Write every entry queued for a followed file through its context buffer.
+    fn emit_queued(
+        &self,
+        state: &mut SourceState,
+        prefix: &str,
+        context: &mut ContextBuffer,
+        output: &mut dyn OutputSink,
+    ) -> Result<(), JlError> {
+        while let Some(entry) = state.entries.pop_front() {
+            self.filter(entry, prefix).write(context, output)?;
+        }
+        Ok(())
+    }
+
+    /// The colored `[file] ` prefix for each followed file, or empty prefixes
+    /// when only a single file is followed.
+    fn file_prefixes(&self, paths: &[PathBuf]) -> Vec<String> {
+        if paths.len() < 2 {
+            return vec![String::new(); paths.len()];
+        }
+        paths
+            .iter()
+            .enumerate()
+            .map(|(i, path)| {
+                let name = format::sanitize_control_chars(&path.display().to_string());
+                format!("{} ", self.color.style_file_prefix(&format!("[{name}]"), i))
+            })
+            .collect()
+    }
+
+    /// Merge several file sources into one stream ordered by timestamp.
+    ///
+    /// A k-way merge: each source's next entry sits in a min-heap keyed by its
+    /// timestamp, and a source is refilled once its entry has been written.
+    /// Entries without a timestamp (plain text, undated records) take the key
+    /// of the previous entry from the same source so they stay next to it.
+    /// Ties go to the source listed first, keeping the merge stable.
+    fn merge_sources(
+        &mut self,
+        mut inputs: Vec<MergeInput>,
+        output: &mut dyn OutputSink,
+    ) -> Result<(), JlError> {
+        let mut heap = BinaryHeap::with_capacity(inputs.len());
+        for (i, input) in inputs.iter_mut().enumerate() {
+            if self.advance(input)? {
+                heap.push(Reverse((input.key, i)));
+            }
+        }
+        while let Some(Reverse((_, i))) = heap.pop() {
+            let input = &mut inputs[i];
+            if let Some(entry) = input.head.take() {
+                self.emit(entry, "", output)?;
+            }
+            if self.advance(input)? {
+                heap.push(Reverse((input.key, i)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the next entry of a merge input into its head slot, updating its
+    /// sort key. Returns false once the source is exhausted.
+    fn advance(&self, input: &mut MergeInput) -> Result<bool, JlError> {
+        input.head = self.next_entry(&mut input.source, &mut input.state)?;
+        if let Some(Entry::Record(record)) = &input.head
+            && record.datetime.is_some()
+        {
+            input.key = record.datetime;
+        }
+        Ok(input.head.is_some())
+    }
+
+    /// Read and decode lines until one produces an entry, or the source ends.
+    fn next_entry(
+        &self,
+        source: &mut dyn LineSource,
+        state: &mut SourceState,
+    ) -> Result<Option<Entry>, JlError> {
+        loop {
+            if let Some(entry) = state.entries.pop_front() {
+                return Ok(Some(entry));
+            }
+            if let Some(line) = state.lines.pop_front() {
+                if let Some(entry) = self.decode(&line, state)? {
+                    state.push_entry(entry);
                 }
+            } else if let Some(line) = source.next_line()? {
+                state.push_line(line);
+            } else if !self.finish_source(state)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// At the end of a source, queue the next piece of input that was held
+    /// back waiting for more. Returns false once nothing is left.
+    fn finish_source(&self, state: &mut SourceState) -> Result<bool, JlError> {
+        if state.finish() {
+            return Ok(true);
+        }
+        // A container line cut short by the end of input is still shown
+        if let Some(envelope) = state.container.flush() {
+            if let Some(entry) = self.decode_container(envelope, state)? {
+                state.push_entry(entry);
+            }
+            return Ok(true);
+        }
+        Ok(state.release())
+    }
+
+    /// Decode one line. Returns `None` for lines skipped by `--non-json skip`
+    /// and for container log chunks waiting for the rest of their line.
+    fn decode(&self, line: &str, state: &mut SourceState) -> Result<Option<Entry>, JlError> {
+        // CRI lines are not JSON, so they are recognized before parsing
+        if let Some(envelope) = Envelope::from_cri(line) {
+            return self.push_envelope(envelope, state);
+        }
+        let parsed = self.parse(line, self.args.non_json)?;
+        if let ParseResult::Json(ref value) = parsed
+            && let Some(envelope) = Envelope::from_value(value)
+        {
+            return self.push_envelope(envelope, state);
+        }
+        self.decode_parsed(parsed, state)
+    }
+
+    /// Parse a line as JSON or logfmt, looking past a plain-text prefix with
+    /// `--find-json`. Lines that are neither are tried against `--line-regex`
+    /// before `mode` applies.
+    fn parse(&self, line: &str, mode: NonJsonMode) -> Result<ParseResult, JlError> {
+        let Some(ref line_format) = self.line_format else {
+            return self.parse_structured(line, mode);
+        };
+        match self.parse_structured(line, NonJsonMode::PrintAsIs)? {
+            ParseResult::NonJson(text) => match line_format.parse(&text) {
+                Some(value) => Ok(ParseResult::Matched(value)),
+                None => parse::non_json(&text, mode),
+            },
+            parsed => Ok(parsed),
+        }
+    }
+
+    fn parse_structured(&self, line: &str, mode: NonJsonMode) -> Result<ParseResult, JlError> {
+        if self.args.find_json {
+            parse::parse_prefixed_line(line, mode)
+        } else {
+            parse::parse_line(line, mode)
+        }
+    }
+
+    /// Turn a parsed line into an entry.
+    fn decode_parsed(
+        &self,
+        parsed: ParseResult,
+        state: &mut SourceState,
+    ) -> Result<Option<Entry>, JlError> {
+        match parsed {
+            ParseResult::Json(value) => Ok(Some(Entry::Record(self.extract(value, state)?))),
+            ParseResult::Prefixed { prefix, value } => {
+                let mut record = self.extract(value, state)?;
+                record.prefix = Some(prefix);
+                Ok(Some(Entry::Record(record)))
+            }
+            ParseResult::Matched(value) => {
+                let args = self.args;
+                let mapping = LineFormat::field_mapping();
+                let mut record = LogRecord::extract(value, &mapping, &args.tz, args.ts_format)?;
+                record.file = state.file.clone();
+                Ok(Some(Entry::Record(record)))
             }
             ParseResult::NonJson(text) => {
-                output.write_line(&format::sanitize_control_chars(&text))?;
-                if args.follow {
-                    output.flush()?;
+                Ok(Some(Entry::Text(format::sanitize_control_chars(&text))))
+            }
+            ParseResult::Skip => Ok(None),
+        }
+    }
+
+    /// Collect a chunk of container output, decoding the line it belongs to
+    /// once that line is complete.
+    fn push_envelope(
+        &self,
+        envelope: Envelope,
+        state: &mut SourceState,
+    ) -> Result<Option<Entry>, JlError> {
+        match state.container.push(envelope) {
+            Some(line) => self.decode_container(line, state),
+            None => Ok(None),
+        }
+    }
+
+    /// Decode the line a container wrote, unwrapped from its Docker or CRI
+    /// envelope. The envelope's time and stream fill in for fields the record
+    /// lacks.
+    fn decode_container(
+        &self,
+        envelope: Envelope,
+        state: &mut SourceState,
+    ) -> Result<Option<Entry>, JlError> {
+        let args = self.args;
+        let parsed = self.parse(&envelope.log, args.non_json)?;
+        let mut entry = self.decode_parsed(parsed, state)?;
+        if let Some(Entry::Record(record)) = &mut entry {
+            envelope.apply_fallbacks(record, &args.tz, args.ts_format)?;
+        }
+        Ok(entry)
+    }
+
+    /// Extract a record from a parsed JSON line using the source's schema.
+    fn extract(
+        &self,
+        value: serde_json::Value,
+        state: &mut SourceState,
+    ) -> Result<LogRecord, JlError> {
+        let args = self.args;
+        // Detect the schema from the first JSON line and reuse it
+        let mapping = state
+            .mapping
+            .get_or_insert_with(|| Schema::from_choice(args.schema, &value).field_mapping());
+        let mut record = LogRecord::extract(value, mapping, &args.tz, args.ts_format)?;
+        record.file = state.file.clone();
+        Ok(record)
+    }
+
+    /// Filter an entry and hand it to the context buffer for writing, with
+    /// `prefix` prepended to each line of the rendered output.
+    fn emit(
+        &mut self,
+        entry: Entry,
+        prefix: &str,
+        output: &mut dyn OutputSink,
+    ) -> Result<(), JlError> {
+        self.filter(entry, prefix).write(&mut self.context, output)
+    }
+
+    /// Decide whether an entry is a hit or only context, rendering it with
+    /// `prefix` prepended to each line.
+    fn filter(&self, entry: Entry, prefix: &str) -> Filtered {
+        match entry {
+            Entry::Record(record) => {
+                if self.keep_record(&record) {
+                    Filtered::Hit(prefix_lines(prefix, &self.render(&record)))
+                } else if self.context.is_enabled() {
+                    Filtered::Context(prefix_lines(prefix, &self.render(&record)))
+                } else {
+                    Filtered::Dropped
                 }
             }
-            ParseResult::Skip => {}
+            Entry::Text(text) => match self.render_ctx.search {
+                Some(ref search) if !search.matches_text(&text) => {
+                    Filtered::Context(prefix_lines(prefix, &text))
+                }
+                Some(ref search) => {
+                    let highlighted = search.highlight(&text, &self.color);
+                    Filtered::Hit(prefix_lines(prefix, &highlighted))
+                }
+                // Text cannot match a record filter; with context it only
+                // shows up around the records that do
+                None if self.context.is_enabled() && self.filters_records() => {
+                    Filtered::Context(prefix_lines(prefix, &text))
+                }
+                None => Filtered::Hit(prefix_lines(prefix, &text)),
+            },
         }
     }
 
-    Ok(())
+    /// Whether any of `--min-level`, `--since`, `--until` or `--where` is set.
+    fn filters_records(&self) -> bool {
+        self.args.min_level.is_some()
+            || self.since.is_some()
+            || self.until.is_some()
+            || self.where_filter.is_some()
+    }
+
+    /// Apply `--min-level`, `--since`, `--until`, `--where` and `--grep` to a record.
+    ///
+    /// Records without a level or a parseable timestamp pass the level and
+    /// time-range filters respectively.
+    fn keep_record(&self, record: &LogRecord) -> bool {
+        if let Some(ref min_level) = self.args.min_level
+            && let Some(ref level) = record.level
+            && level < min_level
+        {
+            return false;
+        }
+        if let Some(ref datetime) = record.datetime {
+            if self.since.is_some_and(|since| *datetime < since) {
+                return false;
+            }
+            if self.until.is_some_and(|until| *datetime > until) {
+                return false;
+            }
+        }
+        if let Some(ref filter) = self.where_filter
+            && !filter.matches(record)
+        {
+            return false;
+        }
+        if let Some(ref search) = self.render_ctx.search
+            && !search.matches(record)
+        {
+            return false;
+        }
+        true
+    }
+
+    fn render(&self, record: &LogRecord) -> String {
+        format::render(
+            record,
+            &self.tokens,
+            &self.color,
+            self.args,
+            &self.render_ctx,
+        )
+    }
+}
+
+/// The context buffer for `-A`, `-B` and `-C`.
+fn context_buffer(args: &Args) -> ContextBuffer {
+    ContextBuffer::new(
+        args.before_context.or(args.context).unwrap_or(0),
+        args.after_context.or(args.context).unwrap_or(0),
+    )
+}
+
+/// Prepend `prefix` to every line of `text`, so that multi-line output such
+/// as stack traces stays attributed to its file.
+fn prefix_lines(prefix: &str, text: &str) -> String {
+    if prefix.is_empty() {
+        return text.to_string();
+    }
+    let mut out = prefix.to_string();
+    out.push_str(&text.replace('\n', &format!("\n{prefix}")));
+    out
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::{ColorMode, LoggerFormat, NonJsonMode, SchemaChoice, TsFormat};
+    use crate::cli::{ColorMode, LinePreset, LoggerFormat, NonJsonMode, SchemaChoice, TsFormat};
     use crate::level::Level;
     use std::io::Write;
     use std::path::PathBuf;
@@ -155,17 +731,36 @@ mod tests {
             omit_fields: None,
             color: ColorMode::Never,
             non_json: NonJsonMode::PrintAsIs,
+            find_json: false,
+            multiline: false,
+            join_continuations: false,
+            line_regex: None,
+            line_format: None,
             schema: SchemaChoice::Auto,
             logger_format: LoggerFormat::AsIs,
             logger_length: 0,
             ts_format: TsFormat::Full,
             min_level: None,
+            where_expr: None,
+            grep: None,
+            grep_fields: None,
+            ignore_case: false,
+            invert: false,
+            after_context: None,
+            before_context: None,
+            context: None,
+            since: None,
+            until: None,
             raw_json: false,
             expanded: false,
             key_color: crate::cli::CliColor::Magenta,
             value_color: crate::cli::CliColor::Cyan,
             tz: "utc".to_string(),
             follow: false,
+            lines: None,
+            from_end: false,
+            retry: false,
+            merge: false,
             output: None,
             completions: None,
             files: vec![],
@@ -240,6 +835,283 @@ mod tests {
         assert!(output.contains("no level"));
     }
 
+    // --- Where expression tests ---
+
+    #[test]
+    fn where_filters_records() {
+        let output = run_with_input(
+            &[
+                r#"{"@timestamp":"2024-01-15T10:30:00Z","level":"INFO","logger_name":"com.acme.Api","message":"ok","status":200}"#,
+                r#"{"@timestamp":"2024-01-15T10:30:01Z","level":"ERROR","logger_name":"com.acme.Api","message":"server error","status":503}"#,
+                r#"{"@timestamp":"2024-01-15T10:30:02Z","level":"ERROR","logger_name":"com.acme.Api","message":"user error","status":500,"user_id":"u1"}"#,
+                r#"{"@timestamp":"2024-01-15T10:30:03Z","level":"ERROR","logger_name":"org.other","message":"other error","status":500}"#,
+            ],
+            |args| {
+                args.where_expr = Some(
+                    r#"status >= 500 && logger ~ "com.acme.*" && !exists(user_id)"#.to_string(),
+                );
+            },
+        );
+        assert!(!output.contains("ok"));
+        assert!(output.contains("server error"));
+        assert!(!output.contains("user error"));
+        assert!(!output.contains("other error"));
+    }
+
+    #[test]
+    fn where_nested_path() {
+        let output = run_with_input(
+            &[
+                r#"{"level":"INFO","message":"get","http":{"method":"GET"}}"#,
+                r#"{"level":"INFO","message":"post","http":{"method":"POST"}}"#,
+            ],
+            |args| {
+                args.where_expr = Some(r#"http.method == "POST""#.to_string());
+            },
+        );
+        assert!(!output.contains("get"));
+        assert!(output.contains("post"));
+    }
+
+    #[test]
+    fn where_invalid_expression_returns_error() {
+        let input = write_input(&[r#"{"level":"INFO","message":"x"}"#]);
+        let mut args = default_args();
+        args.files = vec![input.path().to_path_buf()];
+        args.where_expr = Some("status >=".to_string());
+        assert!(run(args).is_err());
+    }
+
+    // --- Grep tests ---
+
+    #[test]
+    fn grep_matches_extracted_message() {
+        let output = run_with_input(
+            &[
+                r#"{"level":"INFO","message":"connection timeout","logger_name":"app"}"#,
+                r#"{"level":"INFO","message":"all good","logger_name":"timeout-watcher"}"#,
+            ],
+            |args| {
+                args.grep = Some("timeout".to_string());
+            },
+        );
+        assert!(output.contains("connection timeout"));
+        assert!(!output.contains("all good"));
+    }
+
+    #[test]
+    fn grep_does_not_match_json_syntax() {
+        // The raw line contains "level" but the message does not
+        let output = run_with_input(&[r#"{"level":"INFO","message":"hello"}"#], |args| {
+            args.grep = Some("level".to_string());
+        });
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn grep_invert_and_ignore_case() {
+        let output = run_with_input(
+            &[
+                r#"{"level":"INFO","message":"GET /Health"}"#,
+                r#"{"level":"INFO","message":"GET /orders"}"#,
+            ],
+            |args| {
+                args.grep = Some("health".to_string());
+                args.ignore_case = true;
+                args.invert = true;
+            },
+        );
+        assert!(!output.contains("/Health"));
+        assert!(output.contains("/orders"));
+    }
+
+    #[test]
+    fn grep_applies_to_non_json_lines() {
+        let output = run_with_input(&["plain timeout line", "plain other line"], |args| {
+            args.grep = Some("timeout".to_string());
+        });
+        assert!(output.contains("plain timeout line"));
+        assert!(!output.contains("plain other line"));
+    }
+
+    // --- Context tests ---
+
+    fn numbered_lines() -> Vec<String> {
+        let levels = [
+            "DEBUG", "INFO", "ERROR", "DEBUG", "DEBUG", "DEBUG", "INFO", "ERROR", "INFO",
+        ];
+        levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| format!(r#"{{"level":"{level}","message":"msg{i}"}}"#))
+            .collect()
+    }
+
+    #[test]
+    fn before_context_with_min_level() {
+        let lines = numbered_lines();
+        let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let output = run_with_input(&refs, |args| {
+            args.format = "{message}".to_string();
+            args.min_level = Some(Level::Error);
+            args.before_context = Some(1);
+        });
+        assert_eq!(output, "msg1\nmsg2\n--\nmsg6\nmsg7\n");
+    }
+
+    #[test]
+    fn text_lines_are_context_when_records_are_filtered() {
+        let output = run_with_input(
+            &[
+                r#"{"level":"INFO","message":"msg0"}"#,
+                "plain text",
+                r#"{"level":"INFO","message":"msg1"}"#,
+                r#"{"level":"ERROR","message":"msg2"}"#,
+                "trailing text",
+                r#"{"level":"INFO","message":"msg3"}"#,
+            ],
+            |args| {
+                args.format = "{message}".to_string();
+                args.min_level = Some(Level::Error);
+                args.context = Some(1);
+            },
+        );
+        assert_eq!(output, "msg1\nmsg2\ntrailing text\n");
+    }
+
+    #[test]
+    fn context_merges_overlapping_windows() {
+        let lines = numbered_lines();
+        let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let output = run_with_input(&refs, |args| {
+            args.format = "{message}".to_string();
+            args.min_level = Some(Level::Error);
+            args.context = Some(2);
+        });
+        assert_eq!(
+            output,
+            "msg0\nmsg1\nmsg2\nmsg3\nmsg4\nmsg5\nmsg6\nmsg7\nmsg8\n"
+        );
+    }
+
+    #[test]
+    fn after_context_with_grep() {
+        let lines = numbered_lines();
+        let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let output = run_with_input(&refs, |args| {
+            args.format = "{message}".to_string();
+            args.grep = Some("msg[07]".to_string());
+            args.after_context = Some(1);
+        });
+        assert_eq!(output, "msg0\nmsg1\n--\nmsg7\nmsg8\n");
+    }
+
+    #[test]
+    fn explicit_before_overrides_context() {
+        let lines = numbered_lines();
+        let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let output = run_with_input(&refs, |args| {
+            args.format = "{message}".to_string();
+            args.min_level = Some(Level::Error);
+            args.context = Some(1);
+            args.before_context = Some(0);
+        });
+        assert_eq!(output, "msg2\nmsg3\n--\nmsg7\nmsg8\n");
+    }
+
+    // --- Time range tests ---
+
+    fn timed_lines() -> Vec<&'static str> {
+        vec![
+            r#"{"@timestamp":"2024-01-15T09:59:59Z","level":"INFO","message":"before"}"#,
+            r#"{"@timestamp":"2024-01-15T10:00:00Z","level":"INFO","message":"at start"}"#,
+            r#"{"@timestamp":"2024-01-15T10:15:00Z","level":"INFO","message":"inside"}"#,
+            r#"{"@timestamp":"2024-01-15T10:30:00Z","level":"INFO","message":"at end"}"#,
+            r#"{"@timestamp":"2024-01-15T10:30:01Z","level":"INFO","message":"after"}"#,
+            r#"{"level":"INFO","message":"untimed"}"#,
+        ]
+    }
+
+    #[test]
+    fn since_and_until_are_inclusive() {
+        let output = run_with_input(&timed_lines(), |args| {
+            args.format = "{message}".to_string();
+            args.since = Some("2024-01-15T10:00".to_string());
+            args.until = Some("2024-01-15 10:30:00".to_string());
+        });
+        assert_eq!(output, "at start\ninside\nat end\nuntimed\n");
+    }
+
+    #[test]
+    fn since_with_offset() {
+        let output = run_with_input(&timed_lines(), |args| {
+            args.format = "{message}".to_string();
+            args.since = Some("2024-01-15T12:30:00+02:00".to_string());
+        });
+        assert_eq!(output, "at end\nafter\nuntimed\n");
+    }
+
+    #[test]
+    fn relative_since_excludes_old_records() {
+        let output = run_with_input(&timed_lines(), |args| {
+            args.format = "{message}".to_string();
+            args.since = Some("15m".to_string());
+        });
+        assert_eq!(output, "untimed\n");
+    }
+
+    #[test]
+    fn since_seeks_large_sorted_file() {
+        let lines: Vec<String> = (0..5000)
+            .map(|i| {
+                format!(
+                    r#"{{"@timestamp":"2024-01-15T{:02}:{:02}:{:02}Z","level":"INFO","message":"line {i}"}}"#,
+                    i / 3600,
+                    (i / 60) % 60,
+                    i % 60
+                )
+            })
+            .collect();
+        let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let output = run_with_input(&refs, |args| {
+            args.format = "{message}".to_string();
+            args.since = Some("2024-01-15T01:20:00Z".to_string());
+        });
+        let expected: String = (4800..5000).map(|i| format!("line {i}\n")).collect();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn since_seek_keeps_before_context() {
+        let lines: Vec<String> = (0..5000)
+            .map(|i| {
+                format!(
+                    r#"{{"@timestamp":"2024-01-15T{:02}:{:02}:{:02}Z","level":"INFO","message":"line {i}"}}"#,
+                    i / 3600,
+                    (i / 60) % 60,
+                    i % 60
+                )
+            })
+            .collect();
+        let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let output = run_with_input(&refs, |args| {
+            args.format = "{message}".to_string();
+            args.since = Some("2024-01-15T01:20:00Z".to_string());
+            args.before_context = Some(1000);
+        });
+        let expected: String = (3800..5000).map(|i| format!("line {i}\n")).collect();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn invalid_since_returns_error() {
+        let input = write_input(&timed_lines());
+        let mut args = default_args();
+        args.files = vec![input.path().to_path_buf()];
+        args.since = Some("last tuesday".to_string());
+        assert!(run(args).is_err());
+    }
+
     // --- Schema caching tests ---
 
     #[test]
@@ -349,6 +1221,466 @@ mod tests {
         assert!(pos1 < pos2);
     }
 
+    fn run_with_files(inputs: &[&NamedTempFile], args_modifier: impl FnOnce(&mut Args)) -> String {
+        let output = NamedTempFile::new().unwrap();
+        let output_path = output.path().to_owned();
+
+        let mut args = default_args();
+        args.files = inputs.iter().map(|f| f.path().to_path_buf()).collect();
+        args.output = Some(output_path.clone());
+        args_modifier(&mut args);
+
+        run(args).unwrap();
+        std::fs::read_to_string(&output_path).unwrap()
+    }
+
+    #[test]
+    fn merge_interleaves_by_timestamp() {
+        let api = write_input(&[
+            r#"{"@timestamp":"2024-01-15T10:00:00Z","level":"INFO","message":"api 1"}"#,
+            r#"{"@timestamp":"2024-01-15T10:00:03Z","level":"INFO","message":"api 2"}"#,
+        ]);
+        let db = write_input(&[
+            r#"{"@timestamp":"2024-01-15T10:00:01Z","level":"INFO","message":"db 1"}"#,
+            r#"{"@timestamp":"2024-01-15T10:00:02Z","level":"INFO","message":"db 2"}"#,
+            r#"{"@timestamp":"2024-01-15T10:00:04Z","level":"INFO","message":"db 3"}"#,
+        ]);
+        let output = run_with_files(&[&api, &db], |args| {
+            args.format = "{message}".to_string();
+            args.merge = true;
+        });
+        assert_eq!(output, "api 1\ndb 1\ndb 2\napi 2\ndb 3\n");
+    }
+
+    #[test]
+    fn merge_keeps_undated_lines_with_predecessor() {
+        let api = write_input(&[
+            r#"{"@timestamp":"2024-01-15T10:00:00Z","level":"INFO","message":"api 1"}"#,
+            "api continuation",
+            r#"{"@timestamp":"2024-01-15T10:00:02Z","level":"INFO","message":"api 2"}"#,
+        ]);
+        let db = write_input(&[
+            r#"{"@timestamp":"2024-01-15T10:00:01Z","level":"INFO","message":"db 1"}"#,
+        ]);
+        let output = run_with_files(&[&api, &db], |args| {
+            args.format = "{message}".to_string();
+            args.merge = true;
+        });
+        assert_eq!(output, "api 1\napi continuation\ndb 1\napi 2\n");
+    }
+
+    #[test]
+    fn merge_ties_keep_file_order() {
+        let line = r#"{"@timestamp":"2024-01-15T10:00:00Z","level":"INFO","message":"same"}"#;
+        let first = write_input(&[line]);
+        let second = write_input(&[line]);
+        let output = run_with_files(&[&first, &second], |args| {
+            args.format = "{_file}".to_string();
+            args.merge = true;
+        });
+        let expected = format!("{}\n{}\n", first.path().display(), second.path().display());
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn merge_applies_filters() {
+        let api = write_input(&[
+            r#"{"@timestamp":"2024-01-15T10:00:00Z","level":"DEBUG","message":"api debug"}"#,
+            r#"{"@timestamp":"2024-01-15T10:00:02Z","level":"ERROR","message":"api error"}"#,
+        ]);
+        let db = write_input(&[
+            r#"{"@timestamp":"2024-01-15T10:00:01Z","level":"WARN","message":"db warn"}"#,
+        ]);
+        let output = run_with_files(&[&api, &db], |args| {
+            args.format = "{message}".to_string();
+            args.merge = true;
+            args.min_level = Some(Level::Warn);
+        });
+        assert_eq!(output, "db warn\napi error\n");
+    }
+
+    #[test]
+    fn file_placeholder_without_merge() {
+        let input = write_input(&[r#"{"level":"INFO","message":"hi"}"#]);
+        let output = run_with_files(&[&input], |args| {
+            args.format = "{_file}: {message}".to_string();
+        });
+        assert_eq!(output, format!("{}: hi\n", input.path().display()));
+    }
+
+    #[test]
+    fn follow_prefixes_only_for_multiple_files() {
+        let args = default_args();
+        let processor = Processor::new(&args).unwrap();
+        let mut paths = vec![PathBuf::from("api.log")];
+        assert_eq!(processor.file_prefixes(&paths), vec![""]);
+
+        paths.push(PathBuf::from("logs/db.log"));
+        assert_eq!(
+            processor.file_prefixes(&paths),
+            vec!["[api.log] ", "[logs/db.log] "]
+        );
+    }
+
+    #[test]
+    fn prefix_lines_marks_every_line() {
+        assert_eq!(prefix_lines("", "a\nb"), "a\nb");
+        assert_eq!(
+            prefix_lines("[api.log] ", "ERROR failed\n    at Foo.bar"),
+            "[api.log] ERROR failed\n[api.log]     at Foo.bar"
+        );
+    }
+
+    #[test]
+    fn followed_files_keep_separate_context() {
+        let output_file = NamedTempFile::new().unwrap();
+        let mut args = default_args();
+        args.format = "{message}".to_string();
+        args.min_level = Some(Level::Error);
+        args.before_context = Some(1);
+        let processor = Processor::new(&args).unwrap();
+        let mut output = FileSink::new(output_file.path()).unwrap();
+
+        let mut first = SourceState::new(Some(Path::new("a.log")), &args);
+        let mut second = SourceState::new(Some(Path::new("b.log")), &args);
+        let mut first_context = context_buffer(&args);
+        let mut second_context = context_buffer(&args);
+        for (state, message, level) in [(&mut first, "a0", "INFO"), (&mut second, "b0", "ERROR")] {
+            let line = format!(r#"{{"level":"{level}","message":"{message}"}}"#);
+            let entry = processor.decode(&line, state).unwrap().unwrap();
+            state.push_entry(entry);
+        }
+        processor
+            .emit_queued(&mut first, "", &mut first_context, &mut output)
+            .unwrap();
+        processor
+            .emit_queued(&mut second, "", &mut second_context, &mut output)
+            .unwrap();
+        output.flush().unwrap();
+        assert_eq!(std::fs::read_to_string(output_file.path()).unwrap(), "b0\n");
+    }
+
+    #[test]
+    fn follow_missing_file_needs_retry() {
+        let mut args = default_args();
+        args.follow = true;
+        let missing = Path::new("/nonexistent/deploy.log");
+        assert!(Processor::new(&args).unwrap().open_follow(missing).is_err());
+
+        args.retry = true;
+        let mut source = Processor::new(&args).unwrap().open_follow(missing).unwrap();
+        assert_eq!(source.poll_line().unwrap(), None);
+    }
+
+    // --- Docker json-file envelopes ---
+
+    #[test]
+    fn docker_envelope_unwrapped() {
+        let output = run_with_input(
+            &[
+                r#"{"log":"{\"@timestamp\":\"2024-01-15T10:30:00Z\",\"level\":\"INFO\",\"logger_name\":\"app\",\"message\":\"started\"}\n","stream":"stdout","time":"2024-01-15T10:30:01Z"}"#,
+                r#"{"log":"plain text\n","stream":"stderr","time":"2024-01-15T10:30:02Z"}"#,
+            ],
+            |args| {
+                args.format = "{timestamp} {level} {message}".to_string();
+            },
+        );
+        assert_eq!(output, "2024-01-15T10:30:00.000 INFO started\nplain text\n");
+    }
+
+    #[test]
+    fn docker_fallbacks_and_split_lines() {
+        let output = run_with_input(
+            &[
+                r#"{"log":"{\"level\":\"ERROR\",\"msg\":\"long ","stream":"stderr","time":"2024-01-15T10:30:00Z"}"#,
+                r#"{"log":"message\"}\n","stream":"stderr","time":"2024-01-15T10:30:05Z"}"#,
+            ],
+            |args| {
+                args.format = "{timestamp} {level} {message}".to_string();
+                args.add_fields = Some("stream".to_string());
+            },
+        );
+        assert!(output.starts_with("2024-01-15T10:30:00.000 ERROR long message"));
+        assert!(output.contains("stream=stderr"), "{output}");
+    }
+
+    #[test]
+    fn docker_unterminated_line_flushed_at_end() {
+        let output = run_with_input(
+            &[
+                r#"{"log":"{\"level\":\"INFO\",\"msg\":\"cut\"}","stream":"stdout","time":"2024-01-15T10:30:00Z"}"#,
+            ],
+            |args| {
+                args.format = "{level} {message}".to_string();
+            },
+        );
+        assert_eq!(output, "INFO cut\n");
+    }
+
+    #[test]
+    fn cri_lines_unwrapped_and_joined() {
+        let output = run_with_input(
+            &[
+                r#"2024-01-15T10:30:00.123456789Z stdout F {"@timestamp":"2024-01-15T10:30:00Z","level":"INFO","logger_name":"app","message":"ready"}"#,
+                r#"2024-01-15T10:30:01Z stderr P {"level":"ERROR","#,
+                r#"2024-01-15T10:30:02Z stderr F "message":"joined"}"#,
+                "2024-01-15T10:30:03Z stdout F plain text",
+            ],
+            |args| {
+                args.format = "{level} {message}".to_string();
+                args.add_fields = Some("stream".to_string());
+            },
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].starts_with("INFO ready"), "{output}");
+        assert!(lines[0].contains("stream=stdout"));
+        assert!(lines[1].starts_with("ERROR joined"));
+        assert!(lines[1].contains("stream=stderr"));
+        assert_eq!(lines[2], "plain text");
+    }
+
+    #[test]
+    fn cri_lines_kept_with_non_json_skip() {
+        let output = run_with_input(
+            &[
+                r#"2024-01-15T10:30:00Z stdout F {"level":"INFO","msg":"kept"}"#,
+                "not a log line",
+            ],
+            |args| {
+                args.format = "{level} {message}".to_string();
+                args.non_json = NonJsonMode::Skip;
+            },
+        );
+        assert_eq!(output, "INFO kept\n");
+    }
+
+    #[test]
+    fn find_json_keeps_prefix() {
+        let output = run_with_input(
+            &[
+                r#"[pod/web-1/app] {"level":"INFO","msg":"ready"}"#,
+                r#"2024-01-15 10:30:00 {"level":"WARN","msg":"slow"}"#,
+                "no json here",
+            ],
+            |args| {
+                args.format = "{_prefix} | {level} {message}".to_string();
+                args.find_json = true;
+            },
+        );
+        assert_eq!(
+            output,
+            "[pod/web-1/app] | INFO ready\n2024-01-15 10:30:00 | WARN slow\nno json here\n"
+        );
+    }
+
+    #[test]
+    fn prefixed_json_is_text_without_find_json() {
+        let output = run_with_input(
+            &[r#"[pod/web-1/app] {"level":"INFO","msg":"ready"}"#],
+            |args| {
+                args.format = "{level} {message}".to_string();
+            },
+        );
+        assert_eq!(
+            output,
+            "[pod/web-1/app] {\"level\":\"INFO\",\"msg\":\"ready\"}\n"
+        );
+    }
+
+    // --- Multi-line JSON ---
+
+    fn pretty_lines() -> Vec<&'static str> {
+        vec![
+            "{",
+            r#"  "level": "INFO","#,
+            r#"  "msg": "first""#,
+            "}",
+            r#"{"level":"WARN","msg":"second"}{"level":"ERROR","msg":"third"}"#,
+            "plain text",
+        ]
+    }
+
+    #[test]
+    fn multiline_joins_pretty_printed_records() {
+        let output = run_with_input(&pretty_lines(), |args| {
+            args.format = "{level} {message}".to_string();
+            args.multiline = true;
+        });
+        assert_eq!(output, "INFO first\nWARN second\nERROR third\nplain text\n");
+    }
+
+    #[test]
+    fn pretty_printed_records_are_text_without_multiline() {
+        let output = run_with_input(&pretty_lines(), |args| {
+            args.format = "{level} {message}".to_string();
+        });
+        assert!(output.starts_with("{\n  \"level\": \"INFO\",\n"));
+    }
+
+    #[test]
+    fn multiline_applies_with_merge() {
+        let a = write_input(&["{", r#"  "time": "2024-01-15T10:30:02Z", "msg": "a2""#, "}"]);
+        let b = write_input(&[r#"{"time": "2024-01-15T10:30:01Z", "msg": "b1"}"#]);
+        let output = run_with_files(&[&a, &b], |args| {
+            args.format = "{message}".to_string();
+            args.merge = true;
+            args.multiline = true;
+        });
+        assert_eq!(output, "b1\na2\n");
+    }
+
+    // --- Stack trace continuation lines ---
+
+    fn java_trace_lines() -> Vec<&'static str> {
+        vec![
+            r#"{"level":"ERROR","message":"request failed"}"#,
+            "java.lang.IllegalStateException: boom",
+            "\tat com.example.Handler.handle(Handler.java:42)",
+            "Caused by: java.io.IOException: disk full",
+            "\t... 3 more",
+            "Server shutting down",
+            r#"{"level":"INFO","message":"next"}"#,
+            "    at com.example.Other.run(Other.java:7)",
+        ]
+    }
+
+    #[test]
+    fn join_continuations_appends_to_stack_trace() {
+        let output = run_with_input(&java_trace_lines(), |args| {
+            args.format = "{level} {message}".to_string();
+            args.join_continuations = true;
+        });
+        let expected = [
+            "ERROR request failed",
+            "    java.lang.IllegalStateException: boom",
+            "    \tat com.example.Handler.handle(Handler.java:42)",
+            "    Caused by: java.io.IOException: disk full",
+            "    \t... 3 more",
+            "Server shutting down",
+            "INFO next",
+            "        at com.example.Other.run(Other.java:7)",
+        ];
+        assert_eq!(output, expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn continuations_are_loose_lines_by_default() {
+        let output = run_with_input(&java_trace_lines(), |args| {
+            args.format = "{level} {message}".to_string();
+        });
+        assert!(output.contains("ERROR request failed\njava.lang.IllegalStateException: boom\n"));
+    }
+
+    #[test]
+    fn joined_continuations_filtered_with_their_record() {
+        let output = run_with_input(&java_trace_lines(), |args| {
+            args.format = "{level} {message}".to_string();
+            args.join_continuations = true;
+            args.min_level = Some(Level::Warn);
+        });
+        assert!(output.starts_with("ERROR request failed\n"));
+        assert!(output.contains("Server shutting down"));
+        assert!(!output.contains("INFO next"));
+        assert!(!output.contains("Other.java"));
+    }
+
+    // --- logfmt input ---
+
+    #[test]
+    fn logfmt_lines_rendered_and_filtered() {
+        let output = run_with_input(
+            &[
+                r#"time="2024-01-15T10:30:00Z" level=info msg="request served" status=200"#,
+                r#"time="2024-01-15T10:30:01Z" level=error msg="request failed" status=503"#,
+                "not logfmt at all",
+            ],
+            |args| {
+                args.format = "{timestamp} {level} {message}".to_string();
+                args.ts_format = TsFormat::Full;
+                args.where_expr = Some("status >= 500".to_string());
+            },
+        );
+        assert_eq!(
+            output,
+            "2024-01-15T10:30:01.000 ERROR request failed\nnot logfmt at all\n"
+        );
+    }
+
+    // --- --line-regex ---
+
+    const LOG4J_REGEX: &str = r"(?P<timestamp>\S+ \S+) \[(?P<thread>[^]]+)\] (?P<level>\w+)\s+(?P<logger>\S+) - (?P<message>.*)";
+
+    #[test]
+    fn line_regex_turns_text_into_records() {
+        let output = run_with_input(
+            &[
+                "2024-01-15 10:30:00,123 [main] INFO  com.foo.Bar - Server started",
+                "2024-01-15 10:30:01,456 [worker-1] ERROR com.foo.Baz - Request failed",
+                r#"{"@timestamp":"2024-01-15T10:30:02Z","level":"WARN","logger_name":"json","message":"still JSON"}"#,
+                "unmatched text",
+            ],
+            |args| {
+                args.format = "{timestamp} {level} [{logger}] {message}".to_string();
+                args.ts_format = TsFormat::Full;
+                args.line_regex = Some(LOG4J_REGEX.to_string());
+                args.add_fields = Some("thread".to_string());
+            },
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].starts_with("2024-01-15T10:30:00.123 INFO [com.foo.Bar] Server started"));
+        assert!(lines[0].contains("thread=main"));
+        assert!(lines[1].starts_with("2024-01-15T10:30:01.456 ERROR [com.foo.Baz] Request failed"));
+        assert!(lines[2].starts_with("2024-01-15T10:30:02.000 WARN [json] still JSON"));
+        assert_eq!(lines[3], "unmatched text");
+    }
+
+    #[test]
+    fn line_regex_records_are_filtered() {
+        let output = run_with_input(
+            &[
+                "2024-01-15 10:30:00,123 [main] INFO  com.foo.Bar - Server started",
+                "2024-01-15 10:30:01,456 [main] ERROR com.foo.Bar - Request failed",
+                "unmatched text",
+            ],
+            |args| {
+                args.format = "{message}".to_string();
+                args.line_regex = Some(LOG4J_REGEX.to_string());
+                args.min_level = Some(Level::Warn);
+                args.non_json = NonJsonMode::Skip;
+            },
+        );
+        assert_eq!(output, "Request failed\n");
+    }
+
+    #[test]
+    fn invalid_line_regex_returns_error() {
+        let mut args = default_args();
+        args.line_regex = Some("(?P<message>".to_string());
+        assert!(run(args).is_err());
+    }
+
+    #[test]
+    fn access_log_records_filtered_by_status() {
+        let output = run_with_input(
+            &[
+                r#"10.0.0.1 - - [15/Jan/2024:10:30:00 +0000] "GET / HTTP/1.1" 200 512 "-" "curl/8.5.0""#,
+                r#"10.0.0.2 - - [15/Jan/2024:10:30:01 +0000] "GET /missing HTTP/1.1" 404 153 "-" "curl/8.5.0""#,
+                r#"10.0.0.3 - - [15/Jan/2024:10:30:02 +0000] "POST /api HTTP/1.1" 502 0 "-" "curl/8.5.0""#,
+            ],
+            |args| {
+                args.format = "{timestamp} {level} {message}".to_string();
+                args.line_format = Some(LinePreset::Combined);
+                args.where_expr = Some("status >= 400".to_string());
+                args.add_fields = Some("remote_addr".to_string());
+            },
+        );
+        assert_eq!(
+            output,
+            "2024-01-15T10:30:01.000 WARN GET /missing HTTP/1.1 remote_addr=10.0.0.2\n\
+             2024-01-15T10:30:02.000 ERROR POST /api HTTP/1.1 remote_addr=10.0.0.3\n"
+        );
+    }
+
     #[test]
     fn nonexistent_file_returns_error() {
         let mut args = default_args();
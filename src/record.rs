@@ -1,11 +1,13 @@
 use std::collections::BTreeMap;
 
+use chrono::{DateTime, FixedOffset};
 use serde_json::Value;
 
 use crate::cli::TsFormat;
 use crate::error::JlError;
 use crate::level::Level;
-use crate::schema::FieldMapping;
+use crate::message_template;
+use crate::schema::{FieldMapping, LevelFormat, TimestampFormat};
 use crate::timestamp;
 
 /// A structured log record extracted from a JSON log line.
@@ -13,11 +15,20 @@ use crate::timestamp;
 pub struct LogRecord {
     pub level: Option<Level>,
     pub timestamp: Option<String>,
+    /// The parsed timestamp, when the timestamp field could be parsed.
+    pub datetime: Option<DateTime<FixedOffset>>,
     pub logger: Option<String>,
+    /// Whether the logger is a source location (`file:line`) rather than a
+    /// dot-separated logger name.
+    pub logger_is_source: bool,
     pub message: Option<String>,
     pub stack_trace: Option<String>,
     pub extras: BTreeMap<String, Value>,
     pub raw: Value,
+    /// The input file the record was read from, shown by `{_file}`.
+    pub file: Option<String>,
+    /// Plain text that preceded the JSON object on its line, shown by `{_prefix}`.
+    pub prefix: Option<String>,
 }
 
 impl LogRecord {
@@ -38,93 +49,246 @@ impl LogRecord {
                 return Ok(LogRecord {
                     level: None,
                     timestamp: None,
+                    datetime: None,
                     logger: None,
+                    logger_is_source: false,
                     message: Some(value.to_string()),
                     stack_trace: None,
                     extras: BTreeMap::new(),
                     raw: value,
+                    file: None,
+                    prefix: None,
                 });
             }
         };
 
+        // Read the payload's fields as if they were top-level
+        let flattened;
+        let payload = mapping
+            .payload
+            .and_then(|key| Some((key, obj.get(key)?.as_object()?)));
+        let obj = match payload {
+            Some((key, payload)) => {
+                let mut fields = obj.clone();
+                fields.remove(key);
+                for (k, v) in payload {
+                    fields.entry(k.clone()).or_insert_with(|| v.clone());
+                }
+                flattened = fields;
+                &flattened
+            }
+            None => obj,
+        };
+
         // Find matching keys for each canonical field
         let level_key = FieldMapping::find_key(&mapping.level, obj).map(String::from);
         let ts_key = FieldMapping::find_key(&mapping.timestamp, obj).map(String::from);
         let logger_key = FieldMapping::find_key(&mapping.logger, obj).map(String::from);
         let message_key = FieldMapping::find_key(&mapping.message, obj).map(String::from);
         let stack_key = FieldMapping::find_key(&mapping.stack_trace, obj).map(String::from);
+        let template_key = mapping
+            .template
+            .filter(|&key| obj.contains_key(key))
+            .map(String::from);
 
         // Extract level
-        let level = level_key.as_deref().and_then(|key| {
-            let val = obj.get(key)?;
-            parse_level(val)
-        });
+        let level = match level_key.as_deref() {
+            Some(key) => {
+                FieldMapping::get(obj, key).and_then(|val| parse_level(val, mapping.level_format))
+            }
+            None => mapping.level_format.default_level(),
+        };
 
         // Extract and format timestamp
-        let timestamp = match ts_key.as_deref() {
-            Some(key) => match obj.get(key) {
-                Some(val) => match timestamp::parse_timestamp(val) {
-                    Some(ts) => Some(timestamp::format_timestamp(&ts, tz, ts_format)?),
-                    None => Some(value_to_string(val)),
-                },
-                None => None,
-            },
-            None => None,
+        let ts_val = ts_key
+            .as_deref()
+            .and_then(|key| FieldMapping::get(obj, key));
+        let datetime = ts_val.and_then(|val| parse_datetime(val, mapping.timestamp_format));
+        let timestamp = match (ts_val, &datetime) {
+            (Some(_), Some(ts)) => Some(timestamp::format_timestamp(ts, tz, ts_format)?),
+            (Some(val), None) => Some(value_to_string(val)),
+            (None, _) => None,
         };
 
         // Extract logger
+        let logger_is_source = logger_key
+            .as_deref()
+            .is_some_and(|key| mapping.source_location.contains(&key));
         let logger = logger_key
             .as_deref()
-            .and_then(|key| obj.get(key))
-            .map(value_to_string);
+            .and_then(|key| FieldMapping::get(obj, key))
+            .map(|val| logger_to_string(val, logger_is_source));
 
-        // Extract message
+        // Extract message, or render it from the template
         let message = message_key
             .as_deref()
-            .and_then(|key| obj.get(key))
-            .map(value_to_string);
+            .and_then(|key| FieldMapping::get(obj, key))
+            .map(value_to_string)
+            .or_else(|| {
+                let template = obj.get(template_key.as_deref()?)?;
+                Some(message_template::render(&value_to_string(template), obj))
+            });
 
         // Extract stack trace
         let stack_trace = stack_key
             .as_deref()
-            .and_then(|key| obj.get(key))
+            .and_then(|key| FieldMapping::get(obj, key))
             .map(value_to_string);
 
         // Collect canonical keys to exclude from extras
-        let canonical_keys: Vec<&str> =
-            [&level_key, &ts_key, &logger_key, &message_key, &stack_key]
-                .iter()
-                .filter_map(|k| k.as_deref())
-                .collect();
+        let canonical_keys: Vec<&str> = [
+            &level_key,
+            &ts_key,
+            &logger_key,
+            &message_key,
+            &stack_key,
+            &template_key,
+        ]
+        .iter()
+        .filter_map(|k| k.as_deref())
+        .collect();
 
         // Collect remaining fields as extras
-        let extras: BTreeMap<String, Value> = obj
+        let mut extras: BTreeMap<String, Value> = obj
             .iter()
             .filter(|(k, _)| !canonical_keys.contains(&k.as_str()))
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect();
+        // Canonical fields found by a nested path are removed from their parent
+        for key in canonical_keys {
+            if !obj.contains_key(key) {
+                remove_nested(&mut extras, key);
+            }
+        }
 
         Ok(LogRecord {
             level,
             timestamp,
+            datetime,
             logger,
+            logger_is_source,
             message,
             stack_trace,
             extras,
             raw: value,
+            file: None,
+            prefix: None,
         })
     }
+
+    /// Append a line to the stack trace, starting one if the record has none.
+    pub fn append_stack_trace_line(&mut self, line: &str) {
+        match self.stack_trace {
+            Some(ref mut trace) => {
+                trace.push('\n');
+                trace.push_str(line);
+            }
+            None => self.stack_trace = Some(line.to_string()),
+        }
+    }
+
+    /// Look up a field by name for filtering.
+    ///
+    /// Canonical names (`level`, `timestamp`, `logger`, `message`, `stack_trace`)
+    /// resolve to the extracted values. Other names are looked up in `extras`
+    /// first, then as a dot-separated path into nested objects of `raw`.
+    pub fn lookup(&self, path: &str) -> Option<Value> {
+        let canonical = match path {
+            "level" => Some(self.level.map(|l| l.to_string())),
+            "timestamp" => Some(self.timestamp.clone()),
+            "logger" => Some(self.logger.clone()),
+            "message" => Some(self.message.clone()),
+            "stack_trace" => Some(self.stack_trace.clone()),
+            _ => None,
+        };
+        if let Some(field) = canonical {
+            return field.map(Value::String);
+        }
+        if let Some(val) = self.extras.get(path) {
+            return Some(val.clone());
+        }
+        let mut current = &self.raw;
+        for segment in path.split('.') {
+            current = current.as_object()?.get(segment)?;
+        }
+        Some(current.clone())
+    }
 }
 
-/// Parse a level from a JSON value - handles both string and numeric (Bunyan) levels.
-fn parse_level(val: &Value) -> Option<Level> {
-    match val {
-        Value::String(s) => s.parse::<Level>().ok(),
-        Value::Number(n) => n.as_i64().and_then(Level::from_bunyan_int),
+/// Remove the value at a dot-separated path from nested objects, dropping
+/// objects that are left empty.
+fn remove_nested(fields: &mut BTreeMap<String, Value>, path: &str) {
+    fn remove(obj: &mut serde_json::Map<String, Value>, path: &str) {
+        let Some((first, rest)) = path.split_once('.') else {
+            obj.remove(path);
+            return;
+        };
+        if let Some(Value::Object(child)) = obj.get_mut(first) {
+            remove(child, rest);
+            if child.is_empty() {
+                obj.remove(first);
+            }
+        }
+    }
+    let Some((first, rest)) = path.split_once('.') else {
+        return;
+    };
+    if let Some(Value::Object(child)) = fields.get_mut(first) {
+        remove(child, rest);
+        if child.is_empty() {
+            fields.remove(first);
+        }
+    }
+}
+
+/// Parse a level from a JSON value - handles both string (including slog's `INFO+2`)
+/// and numeric (Bunyan) levels, or the syslog severities, Serilog names and GCP
+/// severities of schemas that use them.
+fn parse_level(val: &Value, format: LevelFormat) -> Option<Level> {
+    match (format, val) {
+        (LevelFormat::Standard, Value::String(s)) => s
+            .parse::<Level>()
+            .ok()
+            .or_else(|| Level::from_slog_offset(s)),
+        (LevelFormat::Standard, Value::Number(n)) => n.as_i64().and_then(Level::from_bunyan_int),
+        (LevelFormat::Syslog, Value::String(s)) => {
+            s.parse().ok().and_then(Level::from_syslog_priority)
+        }
+        (LevelFormat::Syslog, Value::Number(n)) => n.as_i64().and_then(Level::from_syslog_priority),
+        (LevelFormat::Serilog, Value::String(s)) => match s.to_ascii_lowercase().as_str() {
+            "verbose" => Some(Level::Trace),
+            "information" => Some(Level::Info),
+            _ => s.parse().ok(),
+        },
+        (LevelFormat::Gcp, Value::String(s)) if s.eq_ignore_ascii_case("default") => None,
+        (LevelFormat::Gcp, _) => parse_level(val, LevelFormat::Standard),
         _ => None,
     }
 }
 
+/// Parse a timestamp value as encoded by the given format.
+fn parse_datetime(val: &Value, format: TimestampFormat) -> Option<DateTime<FixedOffset>> {
+    match (format, val) {
+        (TimestampFormat::EpochString, Value::String(s)) => {
+            timestamp::parse_epoch_string(s).or_else(|| timestamp::parse_timestamp(val))
+        }
+        _ => timestamp::parse_timestamp(val),
+    }
+}
+
+/// Convert a logger value to a display string. Source location objects
+/// (`{"file": "main.go", "line": 42}`) become `main.go:42`.
+fn logger_to_string(val: &Value, is_source: bool) -> String {
+    if is_source && let Some(file) = val.get("file") {
+        let file = value_to_string(file);
+        return match val.get("line") {
+            Some(line) => format!("{file}:{}", value_to_string(line)),
+            None => file,
+        };
+    }
+    value_to_string(val)
+}
+
 /// Convert a JSON value to a display string, stripping quotes from strings.
 fn value_to_string(val: &Value) -> String {
     match val {
@@ -391,6 +555,22 @@ mod tests {
         assert!(ts.contains(".123"));
     }
 
+    #[test]
+    fn extract_keeps_parsed_datetime() {
+        let mapping = Schema::Logstash.field_mapping();
+        let value = json!({
+            "@timestamp": "2024-01-15T10:30:00+02:00",
+            "level": "INFO",
+            "message": "parsed"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Time).unwrap();
+        assert_eq!(record.timestamp.as_deref(), Some("08:30:00.000"));
+        assert_eq!(
+            record.datetime.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00+02:00"
+        );
+    }
+
     #[test]
     fn extract_with_named_timezone() {
         let mapping = Schema::Logstash.field_mapping();
@@ -429,6 +609,7 @@ mod tests {
         let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
         // Unparseable timestamp should be kept as the raw string
         assert_eq!(record.timestamp.as_deref(), Some("not-a-date"));
+        assert!(record.datetime.is_none());
     }
 
     #[test]
@@ -478,6 +659,307 @@ mod tests {
         }
     }
 
+    #[test]
+    fn extract_journald_entry() {
+        let mapping = Schema::Journald.field_mapping();
+        let value = json!({
+            "__CURSOR": "s=abc;i=1",
+            "__REALTIME_TIMESTAMP": "1705314600123456",
+            "_BOOT_ID": "0d5c",
+            "PRIORITY": "3",
+            "SYSLOG_IDENTIFIER": "sshd",
+            "_SYSTEMD_UNIT": "ssh.service",
+            "MESSAGE": "Connection closed"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.timestamp.as_deref(), Some("2024-01-15T10:30:00.123"));
+        assert_eq!(record.logger.as_deref(), Some("sshd"));
+        assert_eq!(record.message.as_deref(), Some("Connection closed"));
+        assert!(record.extras.contains_key("_SYSTEMD_UNIT"));
+    }
+
+    #[test]
+    fn extract_journald_unit_as_logger_fallback() {
+        let mapping = Schema::Journald.field_mapping();
+        let value = json!({
+            "PRIORITY": 6,
+            "_SYSTEMD_UNIT": "nginx.service",
+            "MESSAGE": "Started"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Info));
+        assert_eq!(record.logger.as_deref(), Some("nginx.service"));
+    }
+
+    #[test]
+    fn extract_gcp_exported_entry() {
+        let mapping = Schema::Gcp.field_mapping();
+        let value = json!({
+            "insertId": "1abc",
+            "logName": "projects/shop/logs/stdout",
+            "severity": "NOTICE",
+            "timestamp": "2024-01-15T10:30:00.123Z",
+            "jsonPayload": {"message": "order placed", "order_id": 42},
+            "sourceLocation": {"file": "orders/handler.go", "line": "87", "function": "Place"},
+            "labels": {"env": "prod"}
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Info));
+        assert_eq!(record.timestamp.as_deref(), Some("2024-01-15T10:30:00.123"));
+        assert_eq!(record.message.as_deref(), Some("order placed"));
+        assert_eq!(record.logger.as_deref(), Some("orders/handler.go:87"));
+        assert!(record.logger_is_source);
+        // Payload fields are extras alongside the entry's own fields
+        assert_eq!(record.extras.get("order_id"), Some(&json!(42)));
+        assert_eq!(record.extras.get("labels"), Some(&json!({"env": "prod"})));
+        assert!(!record.extras.contains_key("jsonPayload"));
+        assert_eq!(record.lookup("jsonPayload.order_id"), Some(json!(42)));
+    }
+
+    #[test]
+    fn extract_gcp_text_payload_and_default_severity() {
+        let mapping = Schema::Gcp.field_mapping();
+        let value = json!({
+            "severity": "DEFAULT",
+            "textPayload": "plain line",
+            "timestamp": "2024-01-15T10:30:00Z"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert!(record.level.is_none());
+        assert!(!record.extras.contains_key("severity"));
+        assert_eq!(record.message.as_deref(), Some("plain line"));
+    }
+
+    #[test]
+    fn extract_ecs_flat_keys() {
+        let mapping = Schema::Ecs.field_mapping();
+        let value = json!({
+            "@timestamp": "2024-01-15T10:30:00.123Z",
+            "log.level": "ERROR",
+            "message": "request failed",
+            "ecs.version": "1.2.0",
+            "log.logger": "com.example.Api",
+            "error.stack_trace": "java.io.IOException: reset"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.logger.as_deref(), Some("com.example.Api"));
+        assert_eq!(
+            record.stack_trace.as_deref(),
+            Some("java.io.IOException: reset")
+        );
+        assert_eq!(record.extras.keys().collect::<Vec<_>>(), ["ecs.version"]);
+    }
+
+    #[test]
+    fn extract_ecs_nested_objects() {
+        let mapping = Schema::Ecs.field_mapping();
+        let value = json!({
+            "@timestamp": "2024-01-15T10:30:00.123Z",
+            "log": {"level": "warning", "logger": "app.db", "origin": {"file": {"line": 12}}},
+            "message": "slow query",
+            "ecs": {"version": "1.6.0"}
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Warn));
+        assert_eq!(record.logger.as_deref(), Some("app.db"));
+        assert_eq!(record.message.as_deref(), Some("slow query"));
+        // The rest of the `log` object stays an extra
+        assert_eq!(
+            record.extras.get("log"),
+            Some(&json!({"origin": {"file": {"line": 12}}}))
+        );
+        assert_eq!(record.extras.get("ecs"), Some(&json!({"version": "1.6.0"})));
+    }
+
+    #[test]
+    fn extract_clef_renders_template() {
+        let mapping = Schema::Clef.field_mapping();
+        let value = json!({
+            "@t": "2024-01-15T10:30:00.1234567Z",
+            "@mt": "User {UserId} logged in after {Attempts} attempts",
+            "@i": "a1b2c3d4",
+            "UserId": "alice",
+            "Attempts": 2,
+            "SourceContext": "Shop.Auth.LoginHandler"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Info));
+        assert_eq!(record.timestamp.as_deref(), Some("2024-01-15T10:30:00.123"));
+        assert_eq!(
+            record.message.as_deref(),
+            Some("User alice logged in after 2 attempts")
+        );
+        assert_eq!(record.logger.as_deref(), Some("Shop.Auth.LoginHandler"));
+        assert!(!record.extras.contains_key("@mt"));
+        assert!(record.extras.contains_key("@i"));
+        assert!(record.extras.contains_key("UserId"));
+    }
+
+    #[test]
+    fn extract_clef_prefers_rendered_message() {
+        let mapping = Schema::Clef.field_mapping();
+        let value = json!({
+            "@t": "2024-01-15T10:30:00Z",
+            "@m": "Payment \"p-1\" failed",
+            "@mt": "Payment {PaymentId} failed",
+            "@l": "Error",
+            "@x": "System.InvalidOperationException: declined\n   at Shop.Pay()",
+            "PaymentId": "p-1"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.message.as_deref(), Some("Payment \"p-1\" failed"));
+        assert!(
+            record
+                .stack_trace
+                .unwrap()
+                .starts_with("System.InvalidOperationException")
+        );
+    }
+
+    #[test]
+    fn extract_clef_serilog_level_names() {
+        let mapping = Schema::Clef.field_mapping();
+        for (name, level) in [
+            ("Verbose", Level::Trace),
+            ("Debug", Level::Debug),
+            ("Information", Level::Info),
+            ("Warning", Level::Warn),
+            ("Fatal", Level::Fatal),
+        ] {
+            let value = json!({"@t": "2024-01-15T10:30:00Z", "@m": "x", "@l": name});
+            let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+            assert_eq!(record.level, Some(level), "{name}");
+        }
+    }
+
+    #[test]
+    fn extract_slog_source_and_level_offset() {
+        let mapping = Schema::Slog.field_mapping();
+        let value = json!({
+            "time": "2024-01-15T10:30:00.123456789Z",
+            "level": "WARN+2",
+            "source": {"function": "main.run", "file": "/src/app/main.go", "line": 42},
+            "msg": "retrying"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Warn));
+        assert_eq!(record.logger.as_deref(), Some("/src/app/main.go:42"));
+        assert!(record.logger_is_source);
+        assert_eq!(record.message.as_deref(), Some("retrying"));
+        assert!(!record.extras.contains_key("source"));
+    }
+
+    #[test]
+    fn extract_logger_object_outside_source_location_keys() {
+        let mapping = Schema::Generic.field_mapping();
+        let value = json!({
+            "logger": {"file": "main.go", "line": 42},
+            "msg": "hello"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(
+            record.logger.as_deref(),
+            Some(r#"{"file":"main.go","line":42}"#)
+        );
+        assert!(!record.logger_is_source);
+    }
+
+    #[test]
+    fn extract_zap_entry() {
+        let mapping = Schema::Zap.field_mapping();
+        let value = json!({
+            "level": "error",
+            "ts": 1705314600.123,
+            "caller": "server/handler.go:87",
+            "msg": "request failed",
+            "stacktrace": "main.handle\n\t/src/server/handler.go:87"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.timestamp.as_deref(), Some("2024-01-15T10:30:00.123"));
+        assert_eq!(record.logger.as_deref(), Some("server/handler.go:87"));
+        assert!(record.stack_trace.is_some());
+    }
+
+    #[test]
+    fn extract_zerolog_entry() {
+        let mapping = Schema::Zerolog.field_mapping();
+        let value = json!({
+            "level": "warn",
+            "time": 1705314600,
+            "caller": "/src/app/db.go:17",
+            "message": "slow query",
+            "error": "timeout"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.level, Some(Level::Warn));
+        assert_eq!(record.timestamp.as_deref(), Some("2024-01-15T10:30:00.000"));
+        assert_eq!(record.logger.as_deref(), Some("/src/app/db.go:17"));
+        assert_eq!(record.extras.get("error"), Some(&json!("timeout")));
+    }
+
+    // --- stack trace continuation ---
+
+    #[test]
+    fn append_stack_trace_line_starts_and_extends_trace() {
+        let mapping = Schema::Logstash.field_mapping();
+        let value = json!({"level": "ERROR", "message": "failed"});
+        let mut record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        record.append_stack_trace_line("java.lang.IllegalStateException: boom");
+        record.append_stack_trace_line("\tat com.example.App.run(App.java:42)");
+        assert_eq!(
+            record.stack_trace.as_deref(),
+            Some("java.lang.IllegalStateException: boom\n\tat com.example.App.run(App.java:42)")
+        );
+    }
+
+    // --- lookup tests ---
+
+    #[test]
+    fn lookup_canonical_fields() {
+        let mapping = Schema::Logstash.field_mapping();
+        let value = json!({
+            "level": "WARN",
+            "logger_name": "com.example",
+            "message": "hello"
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.lookup("level"), Some(json!("WARN")));
+        assert_eq!(record.lookup("logger"), Some(json!("com.example")));
+        assert_eq!(record.lookup("message"), Some(json!("hello")));
+        assert_eq!(record.lookup("timestamp"), None);
+    }
+
+    #[test]
+    fn lookup_extras_and_nested_paths() {
+        let mapping = Schema::Logstash.field_mapping();
+        let value = json!({
+            "message": "hello",
+            "status": 500,
+            "http": {"request": {"method": "GET"}}
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.lookup("status"), Some(json!(500)));
+        assert_eq!(record.lookup("http.request.method"), Some(json!("GET")));
+        assert_eq!(record.lookup("http.response"), None);
+        assert_eq!(record.lookup("missing"), None);
+    }
+
+    #[test]
+    fn lookup_prefers_dotted_extra_key_over_path() {
+        let mapping = Schema::Logstash.field_mapping();
+        let value = json!({
+            "message": "hello",
+            "user.id": "flat",
+            "user": {"id": "nested"}
+        });
+        let record = LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap();
+        assert_eq!(record.lookup("user.id"), Some(json!("flat")));
+    }
+
     #[test]
     fn extract_boolean_level_ignored() {
         let mapping = Schema::Logstash.field_mapping();
@@ -1,6 +1,7 @@
 use serde_json::Value;
 
 use crate::cli::SchemaChoice;
+use crate::level::Level;
 
 /// Supported log schemas.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,6 +9,13 @@ pub enum Schema {
     Logstash,
     Logrus,
     Bunyan,
+    Journald,
+    Gcp,
+    Ecs,
+    Clef,
+    Slog,
+    Zap,
+    Zerolog,
     Generic,
 }
 
@@ -20,23 +28,88 @@ pub struct FieldMapping {
     pub timestamp: Vec<&'static str>,
     /// Key name(s) to try for the logger/source field.
     pub logger: Vec<&'static str>,
+    /// Logger keys whose values are source locations rather than logger
+    /// names: `file:line` strings or `{"file": ..., "line": ...}` objects.
+    pub source_location: Vec<&'static str>,
     /// Key name(s) to try for the message field.
     pub message: Vec<&'static str>,
     /// Key name(s) to try for the stack trace field.
     pub stack_trace: Vec<&'static str>,
+    /// How the level field's values are encoded.
+    pub level_format: LevelFormat,
+    /// How the timestamp field's values are encoded.
+    pub timestamp_format: TimestampFormat,
+    /// Key of a nested object whose fields are read as if they were top-level,
+    /// such as GCP's `jsonPayload`. Top-level fields win on conflicts.
+    pub payload: Option<&'static str>,
+    /// Key of a message template, such as CLEF's `@mt`, rendered from the
+    /// record's fields when none of the message keys is present.
+    pub template: Option<&'static str>,
+}
+
+/// How a schema encodes the values of its level field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelFormat {
+    /// Level names such as `info` or `WARNING`, or Bunyan's numeric levels.
+    Standard,
+    /// Syslog severities from 0 (emergency) to 7 (debug), as numbers or strings.
+    Syslog,
+    /// Serilog's `Verbose`, `Debug`, `Information`, `Warning`, `Error` and
+    /// `Fatal`; a missing level means `Information`.
+    Serilog,
+    /// Google Cloud Logging severities: the standard names plus `DEFAULT`,
+    /// which marks an entry with no assigned severity.
+    Gcp,
+}
+
+/// How a schema encodes the values of its timestamp field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// ISO 8601 strings, epoch numbers, or `{seconds, nanos}` objects.
+    Standard,
+    /// Epoch times written as strings of digits, like journald's
+    /// `__REALTIME_TIMESTAMP` in microseconds, as well as the standard forms.
+    EpochString,
+}
+
+impl LevelFormat {
+    /// The level of records that have no level field.
+    pub fn default_level(self) -> Option<Level> {
+        match self {
+            LevelFormat::Serilog => Some(Level::Info),
+            LevelFormat::Standard | LevelFormat::Syslog | LevelFormat::Gcp => None,
+        }
+    }
 }
 
 impl FieldMapping {
     /// Find the first matching key from the candidates in the given JSON object.
+    ///
+    /// A candidate such as `log.level` matches either a top-level key of that
+    /// name or a dot-separated path into nested objects (`{"log": {"level": ..}}`).
     pub fn find_key(
         candidates: &[&'static str],
         obj: &serde_json::Map<String, Value>,
     ) -> Option<&'static str> {
         candidates
             .iter()
-            .find(|&&key| obj.contains_key(key))
+            .find(|&&key| Self::get(obj, key).is_some())
             .copied()
     }
+
+    /// Look up a key found by [`FieldMapping::find_key`], preferring a
+    /// top-level key over a nested path.
+    pub fn get<'a>(obj: &'a serde_json::Map<String, Value>, key: &str) -> Option<&'a Value> {
+        if let Some(value) = obj.get(key) {
+            return Some(value);
+        }
+        let (first, rest) = key.split_once('.')?;
+        let mut current = obj.get(first)?;
+        for segment in rest.split('.') {
+            current = current.as_object()?.get(segment)?;
+        }
+        Some(current)
+    }
 }
 
 impl Schema {
@@ -47,22 +120,125 @@ impl Schema {
                 level: vec!["level"],
                 timestamp: vec!["@timestamp"],
                 logger: vec!["logger_name"],
+                source_location: vec![],
                 message: vec!["message"],
                 stack_trace: vec!["stack_trace"],
+                level_format: LevelFormat::Standard,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: None,
             },
             Schema::Logrus => FieldMapping {
                 level: vec!["level"],
                 timestamp: vec!["time"],
                 logger: vec!["component"],
+                source_location: vec![],
                 message: vec!["msg"],
                 stack_trace: vec!["stack_trace", "stacktrace"],
+                level_format: LevelFormat::Standard,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: None,
             },
             Schema::Bunyan => FieldMapping {
                 level: vec!["level"],
                 timestamp: vec!["time"],
                 logger: vec!["name"],
+                source_location: vec![],
+                message: vec!["msg"],
+                stack_trace: vec!["stack"],
+                level_format: LevelFormat::Standard,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: None,
+            },
+            Schema::Journald => FieldMapping {
+                level: vec!["PRIORITY"],
+                timestamp: vec!["__REALTIME_TIMESTAMP"],
+                logger: vec!["SYSLOG_IDENTIFIER", "_SYSTEMD_UNIT"],
+                source_location: vec![],
+                message: vec!["MESSAGE"],
+                stack_trace: vec![],
+                level_format: LevelFormat::Syslog,
+                timestamp_format: TimestampFormat::EpochString,
+                payload: None,
+                template: None,
+            },
+            Schema::Gcp => FieldMapping {
+                level: vec!["severity"],
+                timestamp: vec!["timestamp", "time"],
+                logger: vec![
+                    "logger",
+                    "logging.googleapis.com/sourceLocation",
+                    "sourceLocation",
+                ],
+                source_location: vec!["logging.googleapis.com/sourceLocation", "sourceLocation"],
+                message: vec!["message", "textPayload"],
+                stack_trace: vec!["stack_trace", "exception"],
+                level_format: LevelFormat::Gcp,
+                timestamp_format: TimestampFormat::Standard,
+                payload: Some("jsonPayload"),
+                template: None,
+            },
+            Schema::Ecs => FieldMapping {
+                level: vec!["log.level"],
+                timestamp: vec!["@timestamp"],
+                logger: vec!["log.logger"],
+                source_location: vec![],
+                message: vec!["message"],
+                stack_trace: vec!["error.stack_trace"],
+                level_format: LevelFormat::Standard,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: None,
+            },
+            Schema::Clef => FieldMapping {
+                level: vec!["@l"],
+                timestamp: vec!["@t"],
+                logger: vec!["SourceContext"],
+                source_location: vec![],
+                message: vec!["@m"],
+                stack_trace: vec!["@x"],
+                level_format: LevelFormat::Serilog,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: Some("@mt"),
+            },
+            Schema::Slog => FieldMapping {
+                level: vec!["level"],
+                timestamp: vec!["time"],
+                logger: vec!["source"],
+                source_location: vec!["source"],
                 message: vec!["msg"],
+                stack_trace: vec![],
+                level_format: LevelFormat::Standard,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: None,
+            },
+            Schema::Zap => FieldMapping {
+                level: vec!["level"],
+                timestamp: vec!["ts"],
+                logger: vec!["logger", "caller"],
+                source_location: vec!["caller"],
+                message: vec!["msg"],
+                stack_trace: vec!["stacktrace"],
+                level_format: LevelFormat::Standard,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: None,
+            },
+            Schema::Zerolog => FieldMapping {
+                level: vec!["level"],
+                timestamp: vec!["time"],
+                logger: vec!["caller"],
+                source_location: vec!["caller"],
+                message: vec!["message"],
                 stack_trace: vec!["stack"],
+                level_format: LevelFormat::Standard,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: None,
             },
             Schema::Generic => FieldMapping {
                 level: vec!["level", "severity", "loglevel", "log_level", "lvl"],
@@ -75,6 +251,7 @@ impl Schema {
                     "source",
                     "caller",
                 ],
+                source_location: vec![],
                 message: vec!["message", "msg", "text", "body", "log"],
                 stack_trace: vec![
                     "stack_trace",
@@ -83,6 +260,10 @@ impl Schema {
                     "exception",
                     "traceback",
                 ],
+                level_format: LevelFormat::Standard,
+                timestamp_format: TimestampFormat::Standard,
+                payload: None,
+                template: None,
             },
         }
     }
@@ -94,6 +275,13 @@ impl Schema {
             SchemaChoice::Logstash => Schema::Logstash,
             SchemaChoice::Logrus => Schema::Logrus,
             SchemaChoice::Bunyan => Schema::Bunyan,
+            SchemaChoice::Journald => Schema::Journald,
+            SchemaChoice::Gcp => Schema::Gcp,
+            SchemaChoice::Ecs => Schema::Ecs,
+            SchemaChoice::Clef => Schema::Clef,
+            SchemaChoice::Slog => Schema::Slog,
+            SchemaChoice::Zap => Schema::Zap,
+            SchemaChoice::Zerolog => Schema::Zerolog,
             SchemaChoice::Generic => Schema::Generic,
         }
     }
@@ -121,6 +309,50 @@ pub fn detect_schema(value: &Value) -> Schema {
         None => return Schema::Generic,
     };
 
+    // journalctl -o json always includes the entry's cursor and boot ID
+    if obj.contains_key("__CURSOR") || obj.contains_key("_BOOT_ID") {
+        return Schema::Journald;
+    }
+
+    // CLEF events always have `@t`, and a rendered message or its template
+    if obj.contains_key("@t") && (obj.contains_key("@mt") || obj.contains_key("@m")) {
+        return Schema::Clef;
+    }
+
+    // ECS loggers always write `ecs.version`, either flat or nested
+    if FieldMapping::get(obj, "ecs.version").is_some() {
+        return Schema::Ecs;
+    }
+
+    // Exported LogEntry objects wrap the record in a payload; structured logs
+    // written for the logging agent use `logging.googleapis.com/*` special fields
+    let is_log_entry = obj.contains_key("jsonPayload")
+        || obj.contains_key("textPayload")
+        || (obj.contains_key("insertId") && obj.contains_key("logName"));
+    if is_log_entry || obj.keys().any(|k| k.starts_with("logging.googleapis.com/")) {
+        return Schema::Gcp;
+    }
+
+    // Go loggers: zap's `ts`, zerolog's `message` and `caller` next to `time`,
+    // and slog's `source` object or level offsets. Plain slog records share
+    // their keys with Logrus and Bunyan and are read just as well by those
+    // mappings.
+    let has = |key| obj.contains_key(key);
+    if has("ts") && has("msg") && has("level") {
+        return Schema::Zap;
+    }
+    if has("time") && has("message") && has("level") && has("caller") && !has("@timestamp") {
+        return Schema::Zerolog;
+    }
+    let has_source = obj.get("source").is_some_and(|s| s.get("file").is_some());
+    let has_offset = obj
+        .get("level")
+        .and_then(Value::as_str)
+        .is_some_and(|l| Level::from_slog_offset(l).is_some());
+    if has("msg") && (has_source || has_offset) {
+        return Schema::Slog;
+    }
+
     let mut logstash_score: i32 = 0;
     let mut logrus_score: i32 = 0;
     let mut bunyan_score: i32 = 0;
@@ -250,6 +482,122 @@ mod tests {
         assert_eq!(detect_schema(&value), Schema::Logstash);
     }
 
+    #[test]
+    fn detect_journald() {
+        let value = json!({
+            "__CURSOR": "s=6f1c;i=2a;b=0d5c;m=1f;t=60f;x=9e",
+            "__REALTIME_TIMESTAMP": "1705314600123456",
+            "_BOOT_ID": "0d5c",
+            "PRIORITY": "6",
+            "SYSLOG_IDENTIFIER": "sshd",
+            "MESSAGE": "Accepted publickey for alice"
+        });
+        assert_eq!(detect_schema(&value), Schema::Journald);
+        // Either marker is enough, even next to fields of other schemas
+        let value = json!({"_BOOT_ID": "0d5c", "level": "info", "msg": "hi"});
+        assert_eq!(detect_schema(&value), Schema::Journald);
+    }
+
+    #[test]
+    fn detect_gcp() {
+        let exported = json!({
+            "insertId": "1abc",
+            "logName": "projects/shop/logs/stdout",
+            "severity": "ERROR",
+            "timestamp": "2024-01-15T10:30:00Z",
+            "jsonPayload": {"message": "failed"}
+        });
+        assert_eq!(detect_schema(&exported), Schema::Gcp);
+        let text = json!({"severity": "INFO", "textPayload": "started"});
+        assert_eq!(detect_schema(&text), Schema::Gcp);
+        let structured = json!({
+            "severity": "WARNING",
+            "message": "slow query",
+            "time": "2024-01-15T10:30:00Z",
+            "logging.googleapis.com/trace": "projects/shop/traces/abc"
+        });
+        assert_eq!(detect_schema(&structured), Schema::Gcp);
+    }
+
+    #[test]
+    fn detect_ecs() {
+        let flat = json!({
+            "@timestamp": "2024-01-15T10:30:00.123Z",
+            "log.level": "INFO",
+            "message": "started",
+            "ecs.version": "1.2.0",
+            "log.logger": "com.example.App"
+        });
+        assert_eq!(detect_schema(&flat), Schema::Ecs);
+        let nested = json!({
+            "@timestamp": "2024-01-15T10:30:00.123Z",
+            "log": {"level": "info", "logger": "app"},
+            "message": "started",
+            "ecs": {"version": "1.6.0"}
+        });
+        assert_eq!(detect_schema(&nested), Schema::Ecs);
+    }
+
+    #[test]
+    fn detect_clef() {
+        let value = json!({
+            "@t": "2024-01-15T10:30:00.1234567Z",
+            "@mt": "User {UserId} logged in",
+            "UserId": "alice"
+        });
+        assert_eq!(detect_schema(&value), Schema::Clef);
+        let value = json!({"@t": "2024-01-15T10:30:00Z", "@m": "Started", "@l": "Warning"});
+        assert_eq!(detect_schema(&value), Schema::Clef);
+    }
+
+    #[test]
+    fn detect_slog() {
+        let value = json!({
+            "time": "2024-01-15T10:30:00.123456789+01:00",
+            "level": "INFO",
+            "source": {"function": "main.run", "file": "/src/app/main.go", "line": 42},
+            "msg": "listening",
+            "port": 8080
+        });
+        assert_eq!(detect_schema(&value), Schema::Slog);
+        let value = json!({"time": "2024-01-15T10:30:00Z", "level": "INFO+2", "msg": "x"});
+        assert_eq!(detect_schema(&value), Schema::Slog);
+        // Without either, the keys are shared with Logrus and Bunyan
+        let value = json!({"time": "2024-01-15T10:30:00Z", "level": "INFO", "msg": "x"});
+        assert_eq!(detect_schema(&value), Schema::Bunyan);
+    }
+
+    #[test]
+    fn detect_zap() {
+        let value = json!({
+            "level": "info",
+            "ts": 1705314600.123,
+            "logger": "api",
+            "caller": "server/main.go:42",
+            "msg": "started"
+        });
+        assert_eq!(detect_schema(&value), Schema::Zap);
+    }
+
+    #[test]
+    fn detect_zerolog() {
+        let value = json!({
+            "level": "warn",
+            "time": "2024-01-15T10:30:00Z",
+            "caller": "/src/app/db.go:17",
+            "message": "slow query"
+        });
+        assert_eq!(detect_schema(&value), Schema::Zerolog);
+        // Without `caller`, the keys are too common to be zerolog's
+        let value = json!({
+            "level": "warn",
+            "time": "2024-01-15T10:30:00Z",
+            "logger": "db",
+            "message": "slow query"
+        });
+        assert_ne!(detect_schema(&value), Schema::Zerolog);
+    }
+
     #[test]
     fn detect_generic_fallback_non_object() {
         let value = json!("just a string");
@@ -312,6 +660,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_choice_forced_journald() {
+        let value = json!({"MESSAGE": "test"});
+        assert_eq!(
+            Schema::from_choice(SchemaChoice::Journald, &value),
+            Schema::Journald
+        );
+    }
+
+    #[test]
+    fn from_choice_forced_gcp() {
+        let value = json!({"message": "test"});
+        assert_eq!(Schema::from_choice(SchemaChoice::Gcp, &value), Schema::Gcp);
+    }
+
+    #[test]
+    fn from_choice_forced_ecs() {
+        let value = json!({"message": "test"});
+        assert_eq!(Schema::from_choice(SchemaChoice::Ecs, &value), Schema::Ecs);
+    }
+
+    #[test]
+    fn from_choice_forced_clef() {
+        let value = json!({"message": "test"});
+        assert_eq!(
+            Schema::from_choice(SchemaChoice::Clef, &value),
+            Schema::Clef
+        );
+    }
+
+    #[test]
+    fn from_choice_forced_go_loggers() {
+        let value = json!({"message": "test"});
+        assert_eq!(
+            Schema::from_choice(SchemaChoice::Slog, &value),
+            Schema::Slog
+        );
+        assert_eq!(Schema::from_choice(SchemaChoice::Zap, &value), Schema::Zap);
+        assert_eq!(
+            Schema::from_choice(SchemaChoice::Zerolog, &value),
+            Schema::Zerolog
+        );
+    }
+
     #[test]
     fn from_choice_forced_generic() {
         let value = json!({"@timestamp": "2024-01-15T10:30:00Z", "level": "INFO"});
@@ -365,6 +757,61 @@ mod tests {
         assert_eq!(mapping.message, vec!["msg"]);
     }
 
+    #[test]
+    fn journald_mapping() {
+        let mapping = Schema::Journald.field_mapping();
+        assert_eq!(mapping.level, vec!["PRIORITY"]);
+        assert_eq!(mapping.timestamp, vec!["__REALTIME_TIMESTAMP"]);
+        assert_eq!(mapping.logger, vec!["SYSLOG_IDENTIFIER", "_SYSTEMD_UNIT"]);
+        assert_eq!(mapping.message, vec!["MESSAGE"]);
+        assert_eq!(mapping.level_format, LevelFormat::Syslog);
+    }
+
+    #[test]
+    fn gcp_mapping() {
+        let mapping = Schema::Gcp.field_mapping();
+        assert_eq!(mapping.level, vec!["severity"]);
+        assert_eq!(mapping.timestamp, vec!["timestamp", "time"]);
+        assert_eq!(mapping.message, vec!["message", "textPayload"]);
+        assert_eq!(mapping.payload, Some("jsonPayload"));
+    }
+
+    #[test]
+    fn ecs_mapping() {
+        let mapping = Schema::Ecs.field_mapping();
+        assert_eq!(mapping.level, vec!["log.level"]);
+        assert_eq!(mapping.timestamp, vec!["@timestamp"]);
+        assert_eq!(mapping.logger, vec!["log.logger"]);
+        assert_eq!(mapping.message, vec!["message"]);
+        assert_eq!(mapping.stack_trace, vec!["error.stack_trace"]);
+    }
+
+    #[test]
+    fn clef_mapping() {
+        let mapping = Schema::Clef.field_mapping();
+        assert_eq!(mapping.level, vec!["@l"]);
+        assert_eq!(mapping.timestamp, vec!["@t"]);
+        assert_eq!(mapping.message, vec!["@m"]);
+        assert_eq!(mapping.stack_trace, vec!["@x"]);
+        assert_eq!(mapping.template, Some("@mt"));
+        assert_eq!(mapping.level_format.default_level(), Some(Level::Info));
+    }
+
+    #[test]
+    fn go_logger_mappings() {
+        let mapping = Schema::Slog.field_mapping();
+        assert_eq!(mapping.logger, vec!["source"]);
+        assert_eq!(mapping.message, vec!["msg"]);
+        let mapping = Schema::Zap.field_mapping();
+        assert_eq!(mapping.timestamp, vec!["ts"]);
+        assert_eq!(mapping.logger, vec!["logger", "caller"]);
+        assert_eq!(mapping.stack_trace, vec!["stacktrace"]);
+        let mapping = Schema::Zerolog.field_mapping();
+        assert_eq!(mapping.timestamp, vec!["time"]);
+        assert_eq!(mapping.logger, vec!["caller"]);
+        assert_eq!(mapping.message, vec!["message"]);
+    }
+
     #[test]
     fn generic_mapping_has_multiple_candidates() {
         let mapping = Schema::Generic.field_mapping();
@@ -431,6 +878,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn find_key_follows_dotted_paths() {
+        let obj = json!({"log": {"level": "warn", "origin": {"file": "app.py"}}});
+        let map = obj.as_object().unwrap();
+        assert_eq!(
+            FieldMapping::find_key(&["level", "log.level"], map),
+            Some("log.level")
+        );
+        assert_eq!(
+            FieldMapping::get(map, "log.origin.file"),
+            Some(&json!("app.py"))
+        );
+        assert_eq!(FieldMapping::find_key(&["log.logger"], map), None);
+        assert_eq!(FieldMapping::find_key(&["log.level.name"], map), None);
+    }
+
+    #[test]
+    fn find_key_prefers_flat_dotted_key() {
+        let obj = json!({"log.level": "INFO", "log": {"level": "debug"}});
+        let map = obj.as_object().unwrap();
+        assert_eq!(FieldMapping::get(map, "log.level"), Some(&json!("INFO")));
+    }
+
     // --- Detection edge cases ---
 
     #[test]
@@ -0,0 +1,199 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::cli::Args;
+use crate::color::ColorConfig;
+use crate::error::JlError;
+use crate::format::display_value;
+use crate::record::LogRecord;
+
+/// A compiled `--grep` search over the human-visible fields of a record.
+///
+/// By default only the extracted message is searched; `--grep-fields` selects
+/// other fields by the same names accepted by `--where` (canonical fields,
+/// extras, or dotted paths into nested objects).
+#[derive(Debug, Clone)]
+pub struct Search {
+    regex: Regex,
+    fields: Vec<String>,
+    invert: bool,
+}
+
+impl Search {
+    /// Build a search from CLI args. Returns `None` when `--grep` is not set.
+    pub fn from_args(args: &Args) -> Result<Option<Search>, JlError> {
+        let Some(pattern) = args.grep.as_deref() else {
+            return Ok(None);
+        };
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(args.ignore_case)
+            .build()
+            .map_err(|e| JlError::Parse(format!("invalid --grep pattern: {e}")))?;
+        let mut fields: Vec<String> = args
+            .grep_fields
+            .as_deref()
+            .unwrap_or("message")
+            .split(',')
+            .map(|f| f.trim().to_string())
+            .filter(|f| !f.is_empty())
+            .collect();
+        if fields.is_empty() {
+            fields.push("message".to_string());
+        }
+        Ok(Some(Search {
+            regex,
+            fields,
+            invert: args.invert,
+        }))
+    }
+
+    /// Whether the record should be kept: any searched field matches the
+    /// pattern, or none does when `--invert` is set. Fields are matched as
+    /// they are displayed, control characters stripped, just as they are
+    /// highlighted.
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        let found = self.fields.iter().any(|field| {
+            record
+                .lookup(field)
+                .is_some_and(|val| self.regex.is_match(&display_value(&val)))
+        });
+        found != self.invert
+    }
+
+    /// Whether a plain (non-JSON) line should be kept.
+    pub fn matches_text(&self, text: &str) -> bool {
+        self.regex.is_match(text) != self.invert
+    }
+
+    /// Whether matches in the given field should be highlighted when rendering.
+    /// Inverted searches never highlight, since kept records have no matches.
+    pub fn highlights(&self, field: &str) -> bool {
+        !self.invert && self.fields.iter().any(|f| f == field)
+    }
+
+    /// Wrap every match in `text` with the match highlight style.
+    pub fn highlight(&self, text: &str, color: &ColorConfig) -> String {
+        if !color.enabled || self.invert {
+            return text.to_string();
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for m in self.regex.find_iter(text) {
+            if m.is_empty() {
+                continue;
+            }
+            out.push_str(&text[last..m.start()]);
+            out.push_str(&color.style_match(m.as_str()));
+            last = m.end();
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TsFormat;
+    use crate::schema::Schema;
+    use clap::Parser;
+    use serde_json::json;
+
+    fn search(argv: &[&str]) -> Search {
+        let mut full = vec!["jl"];
+        full.extend_from_slice(argv);
+        Search::from_args(&Args::parse_from(full)).unwrap().unwrap()
+    }
+
+    fn record(value: serde_json::Value) -> LogRecord {
+        let mapping = Schema::Logstash.field_mapping();
+        LogRecord::extract(value, &mapping, "utc", TsFormat::Full).unwrap()
+    }
+
+    #[test]
+    fn no_grep_returns_none() {
+        let args = Args::parse_from(["jl"]);
+        assert!(Search::from_args(&args).unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_pattern_is_error() {
+        let args = Args::parse_from(["jl", "--grep", "("]);
+        let err = Search::from_args(&args).unwrap_err();
+        assert!(format!("{err}").contains("invalid --grep pattern"));
+    }
+
+    #[test]
+    fn matches_message_by_default() {
+        let s = search(&["--grep", "timeout"]);
+        assert!(s.matches(&record(json!({"message": "connection timeout"}))));
+        assert!(!s.matches(&record(json!({"message": "ok", "detail": "timeout"}))));
+    }
+
+    #[test]
+    fn matches_displayed_text_of_escape_sequences() {
+        let s = search(&["--grep", "timeout"]);
+        let message = json!("\u{1b}[31mtime\u{1b}out");
+        assert!(s.matches(&record(json!({"message": message}))));
+        let color = ColorConfig::with_enabled(true);
+        let shown = s.highlight(&display_value(&message), &color);
+        assert_eq!(shown, format!("[31m{}", color.style_match("timeout")));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let s = search(&["--grep", "timeout", "-i"]);
+        assert!(s.matches(&record(json!({"message": "Connection TIMEOUT"}))));
+        let s = search(&["--grep", "timeout"]);
+        assert!(!s.matches(&record(json!({"message": "Connection TIMEOUT"}))));
+    }
+
+    #[test]
+    fn invert_keeps_non_matching() {
+        let s = search(&["--grep", "health", "--invert"]);
+        assert!(!s.matches(&record(json!({"message": "GET /health"}))));
+        assert!(s.matches(&record(json!({"message": "GET /orders"}))));
+    }
+
+    #[test]
+    fn selected_fields() {
+        let s = search(&["--grep", "^db", "--grep-fields", "message,host,ctx.node"]);
+        assert!(s.matches(&record(json!({"message": "x", "host": "db-1"}))));
+        assert!(s.matches(&record(json!({"message": "x", "ctx": {"node": "db-2"}}))));
+        assert!(!s.matches(&record(json!({"message": "x", "other": "db-3"}))));
+    }
+
+    #[test]
+    fn matches_text_respects_invert() {
+        let s = search(&["--grep", "panic"]);
+        assert!(s.matches_text("thread panicked"));
+        let s = search(&["--grep", "panic", "--invert"]);
+        assert!(!s.matches_text("thread panicked"));
+    }
+
+    #[test]
+    fn highlight_wraps_all_matches() {
+        let s = search(&["--grep", "o+"]);
+        let color = ColorConfig::with_enabled(true);
+        let out = s.highlight("foo boo", &color);
+        assert_eq!(out.matches("\x1b[").count() % 2, 0);
+        assert!(out.contains(&color.style_match("oo")));
+        assert!(out.starts_with('f'));
+    }
+
+    #[test]
+    fn highlight_plain_without_color() {
+        let s = search(&["--grep", "o+"]);
+        let color = ColorConfig::with_enabled(false);
+        assert_eq!(s.highlight("foo", &color), "foo");
+    }
+
+    #[test]
+    fn highlights_only_selected_fields() {
+        let s = search(&["--grep", "x", "--grep-fields", "message,host"]);
+        assert!(s.highlights("message"));
+        assert!(s.highlights("host"));
+        assert!(!s.highlights("pid"));
+        let s = search(&["--grep", "x", "--invert"]);
+        assert!(!s.highlights("message"));
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, FixedOffset, Local, TimeZone, Utc};
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use chrono_tz::Tz;
 use serde_json::Value;
 
@@ -11,6 +11,8 @@ use crate::error::JlError;
 /// - ISO 8601 strings (e.g. "2024-01-15T10:30:00Z", "2024-01-15T10:30:00+05:30")
 /// - Epoch seconds as f64 or i64
 /// - Epoch milliseconds as i64 (values >= 1e12)
+/// - Epoch microseconds and nanoseconds as i64 (values >= 1e15 and >= 1e18)
+/// - `{"seconds": ..., "nanos": ...}` objects, as accepted by Google Cloud Logging
 pub fn parse_timestamp(value: &Value) -> Option<DateTime<FixedOffset>> {
     match value {
         Value::String(s) => parse_iso8601(s),
@@ -21,6 +23,17 @@ pub fn parse_timestamp(value: &Value) -> Option<DateTime<FixedOffset>> {
                 None
             }
         }
+        Value::Object(obj) => {
+            let integer = |v: &Value| match v {
+                Value::String(s) => s.parse().ok(),
+                _ => v.as_i64(),
+            };
+            let secs = integer(obj.get("seconds")?)?;
+            let nanos = obj.get("nanos").map_or(Some(0), integer)?;
+            Utc.timestamp_opt(secs, u32::try_from(nanos).ok()?)
+                .single()
+                .map(|dt| dt.fixed_offset())
+        }
         _ => None,
     }
 }
@@ -44,27 +57,62 @@ fn parse_iso8601(s: &str) -> Option<DateTime<FixedOffset>> {
     if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
         return Some(dt.and_utc().fixed_offset());
     }
+    // log4j and Python logging separate milliseconds with a comma
+    if let Some((head, frac)) = s.rsplit_once(',')
+        && !frac.is_empty()
+        && frac.bytes().all(|b| b.is_ascii_digit())
+    {
+        return parse_iso8601(&format!("{head}.{frac}"));
+    }
     None
 }
 
+/// Parse an epoch timestamp written as a string of digits, with an optional
+/// fraction, such as journald's `"1705314600123456"`. The magnitude decides
+/// the unit, as for numeric epoch values.
+pub fn parse_epoch_string(s: &str) -> Option<DateTime<FixedOffset>> {
+    let is_numeric = s.starts_with(|c: char| c.is_ascii_digit())
+        && s.bytes().all(|b| b.is_ascii_digit() || b == b'.');
+    if !is_numeric {
+        return None;
+    }
+    parse_epoch(s.parse().ok()?)
+}
+
 /// Parse an epoch-based numeric timestamp.
-/// Values >= 1e12 are treated as milliseconds, otherwise as seconds.
+/// Values >= 1e18 are treated as nanoseconds, >= 1e15 as microseconds,
+/// >= 1e12 as milliseconds, otherwise as seconds.
 fn parse_epoch(value: f64) -> Option<DateTime<FixedOffset>> {
     if !value.is_finite() {
         return None;
     }
-    let (secs, nanos) = if value.abs() >= 1e12 {
+    let (secs, nanos) = if value.abs() >= 1e18 {
+        let nanos = value as i64;
+        (
+            nanos.div_euclid(1_000_000_000),
+            nanos.rem_euclid(1_000_000_000) as u32,
+        )
+    } else if value.abs() >= 1e15 {
+        let micros = value as i64;
+        let secs = micros.div_euclid(1_000_000);
+        let remaining_micros = micros.rem_euclid(1_000_000) as u32;
+        (secs, remaining_micros * 1_000)
+    } else if value.abs() >= 1e12 {
         // Epoch milliseconds - use Euclidean division for correct negative handling
         let millis = value as i64;
         let secs = millis.div_euclid(1000);
         let remaining_millis = millis.rem_euclid(1000) as u32;
         (secs, remaining_millis * 1_000_000)
     } else {
-        // Epoch seconds - use floor for correct negative value handling
+        // Epoch seconds - use floor for correct negative value handling.
+        // An f64 of this size is only precise to a fraction of a microsecond,
+        // so round to whole microseconds (zap's `1705314600.123` is .123, not .122999906)
         let secs = value.floor() as i64;
-        let frac = value - value.floor();
-        let nanos = (frac * 1e9) as u32;
-        (secs, nanos)
+        let micros = ((value - value.floor()) * 1e6).round() as i64;
+        (
+            secs + micros / 1_000_000,
+            (micros % 1_000_000) as u32 * 1_000,
+        )
     };
 
     Utc.timestamp_opt(secs, nanos)
@@ -101,9 +149,7 @@ pub fn format_timestamp(
             utc_dt.format(fmt).to_string()
         }
         _ => {
-            let named_tz: Tz = tz
-                .parse()
-                .map_err(|_| JlError::Tz(format!("unknown timezone: {tz}")))?;
+            let named_tz = parse_named_tz(tz)?;
             let converted = ts.with_timezone(&named_tz);
             converted.format(fmt).to_string()
         }
@@ -111,6 +157,111 @@ pub fn format_timestamp(
     Ok(formatted)
 }
 
+/// Parse a `--since` / `--until` bound relative to `now`.
+///
+/// Accepts:
+/// - Relative durations: `30s`, `15m`, `2h`, `1d`, `1w`, optionally followed by `ago`
+///   (`"2h ago"`, `"15 minutes ago"`), and `now`
+/// - Anything `parse_timestamp` accepts as a string (RFC 3339 and ISO 8601 variants)
+/// - Date and time without seconds: `2024-01-15T10:00`, `2024-01-15 10:00`
+/// - A bare date: `2024-01-15` (midnight)
+/// - A bare time: `10:30`, `10:30:15` (today)
+///
+/// Values without an explicit offset are interpreted in the `tz` display timezone.
+pub fn parse_time_bound(
+    s: &str,
+    tz: &str,
+    now: DateTime<Utc>,
+) -> Result<DateTime<FixedOffset>, JlError> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("now") {
+        return Ok(now.fixed_offset());
+    }
+    if let Some(duration) = parse_relative(s) {
+        return duration
+            .and_then(|duration| now.checked_sub_signed(duration))
+            .map(|dt| dt.fixed_offset())
+            .ok_or_else(|| JlError::Parse(format!("invalid time bound {s:?}: out of range")));
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt);
+    }
+
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+    .or_else(|| {
+        chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .map(|d| d.and_time(NaiveTime::MIN))
+    });
+    if let Some(naive) = naive {
+        return localize(naive, tz);
+    }
+
+    let time = ["%H:%M:%S%.f", "%H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveTime::parse_from_str(s, fmt).ok());
+    if let Some(time) = time {
+        let today = match tz.to_ascii_lowercase().as_str() {
+            "local" => now.with_timezone(&Local).date_naive(),
+            "utc" => now.date_naive(),
+            _ => now.with_timezone(&parse_named_tz(tz)?).date_naive(),
+        };
+        return localize(today.and_time(time), tz);
+    }
+
+    Err(JlError::Parse(format!("invalid time: {s:?}")))
+}
+
+/// Parse a relative duration such as `15m`, `2h ago` or `3 days ago`.
+///
+/// Returns `None` if `s` is not a relative duration, and `Some(None)` if it
+/// is one whose amount is too large to represent.
+fn parse_relative(s: &str) -> Option<Option<Duration>> {
+    let s = s.strip_suffix("ago").unwrap_or(s).trim_end();
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = s.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+    let duration = match unit.trim() {
+        "s" | "sec" | "secs" | "second" | "seconds" => Duration::try_seconds(amount),
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(amount),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(amount),
+        "d" | "day" | "days" => Duration::try_days(amount),
+        "w" | "week" | "weeks" => Duration::try_weeks(amount),
+        _ => return None,
+    };
+    Some(duration)
+}
+
+/// Interpret a naive date-time in the given display timezone.
+fn localize(naive: NaiveDateTime, tz: &str) -> Result<DateTime<FixedOffset>, JlError> {
+    let nonexistent = || JlError::Parse(format!("nonexistent local time: {naive}"));
+    match tz.to_ascii_lowercase().as_str() {
+        "local" => Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|dt| dt.fixed_offset())
+            .ok_or_else(nonexistent),
+        "utc" => Ok(naive.and_utc().fixed_offset()),
+        _ => parse_named_tz(tz)?
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|dt| dt.fixed_offset())
+            .ok_or_else(nonexistent),
+    }
+}
+
+fn parse_named_tz(tz: &str) -> Result<Tz, JlError> {
+    tz.parse()
+        .map_err(|_| JlError::Tz(format!("unknown timezone: {tz}")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,6 +312,15 @@ mod tests {
         assert_eq!(ts.timestamp_subsec_millis(), 456);
     }
 
+    #[test]
+    fn parse_iso8601_comma_fraction() {
+        let val = json!("2024-01-15 10:30:00,123");
+        let ts = parse_timestamp(&val).unwrap();
+        assert_eq!(ts.timestamp(), 1705314600);
+        assert_eq!(ts.timestamp_subsec_millis(), 123);
+        assert!(parse_timestamp(&json!("2024-01-15 10:30:00,")).is_none());
+    }
+
     #[test]
     fn parse_epoch_seconds_integer() {
         let val = json!(1705314600);
@@ -176,6 +336,14 @@ mod tests {
         assert!(ts.timestamp_subsec_millis() >= 499 && ts.timestamp_subsec_millis() <= 501);
     }
 
+    #[test]
+    fn parse_epoch_seconds_float_rounds_to_micros() {
+        let ts = parse_timestamp(&json!(1705314600.123)).unwrap();
+        assert_eq!(ts.to_rfc3339(), "2024-01-15T10:30:00.123+00:00");
+        let ts = parse_timestamp(&json!(1705314600.9999999)).unwrap();
+        assert_eq!(ts.to_rfc3339(), "2024-01-15T10:30:01+00:00");
+    }
+
     #[test]
     fn parse_epoch_millis() {
         let val = json!(1705314600000_i64);
@@ -190,6 +358,34 @@ mod tests {
         assert_eq!(ts.timestamp_subsec_millis(), 123);
     }
 
+    #[test]
+    fn parse_epoch_micros_and_nanos() {
+        let val = json!(1705314600123456_i64);
+        let ts = parse_timestamp(&val).unwrap();
+        assert_eq!(ts.to_rfc3339(), "2024-01-15T10:30:00.123456+00:00");
+
+        let val = json!(1705314600123456789_i64);
+        let ts = parse_timestamp(&val).unwrap();
+        assert_eq!(ts.timestamp(), 1705314600);
+        assert_eq!(ts.timestamp_subsec_millis(), 123);
+    }
+
+    #[test]
+    fn parse_epoch_strings() {
+        let ts = parse_epoch_string("1705314600123456").unwrap();
+        assert_eq!(ts.to_rfc3339(), "2024-01-15T10:30:00.123456+00:00");
+        let ts = parse_epoch_string("1705314600.5").unwrap();
+        assert_eq!(ts.timestamp_subsec_millis(), 500);
+        assert!(parse_epoch_string("1e9").is_none());
+        assert!(parse_epoch_string("NaN").is_none());
+    }
+
+    #[test]
+    fn digit_strings_are_not_timestamps() {
+        assert!(parse_timestamp(&json!("1705314600123456")).is_none());
+        assert!(parse_timestamp(&json!("20240115")).is_none());
+    }
+
     #[test]
     fn parse_invalid_string() {
         let val = json!("not a timestamp");
@@ -220,6 +416,16 @@ mod tests {
         assert!(parse_timestamp(&val).is_none());
     }
 
+    #[test]
+    fn parse_seconds_nanos_object() {
+        let val = json!({"seconds": 1705314600, "nanos": 123000000});
+        let ts = parse_timestamp(&val).unwrap();
+        assert_eq!(ts.to_rfc3339(), "2024-01-15T10:30:00.123+00:00");
+        let val = json!({"seconds": "1705314600"});
+        assert_eq!(parse_timestamp(&val).unwrap().timestamp(), 1705314600);
+        assert!(parse_timestamp(&json!({"seconds": 1, "nanos": -1})).is_none());
+    }
+
     #[test]
     fn parse_empty_string() {
         let val = json!("");
@@ -333,4 +539,81 @@ mod tests {
         let result = format_timestamp(&ts, "Invalid/Timezone", TsFormat::Time);
         assert!(result.is_err());
     }
+
+    // --- parse_time_bound tests ---
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn time_bound_relative() {
+        let bound = parse_time_bound("15m", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-15T11:45:00+00:00");
+        let bound = parse_time_bound("2h ago", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-15T10:00:00+00:00");
+        let bound = parse_time_bound("3 days ago", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-12T12:00:00+00:00");
+        let bound = parse_time_bound("30s", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-15T11:59:30+00:00");
+        let bound = parse_time_bound("1w", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-08T12:00:00+00:00");
+    }
+
+    #[test]
+    fn time_bound_now() {
+        let bound = parse_time_bound("now", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-15T12:00:00+00:00");
+    }
+
+    #[test]
+    fn time_bound_rfc3339() {
+        let bound = parse_time_bound("2024-01-15T10:00:00+02:00", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-15T10:00:00+02:00");
+    }
+
+    #[test]
+    fn time_bound_naive_datetime_uses_tz() {
+        let bound = parse_time_bound("2024-01-15T10:00", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-15T10:00:00+00:00");
+        let bound = parse_time_bound("2024-01-15 10:00:30", "Asia/Tokyo", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-15T10:00:30+09:00");
+    }
+
+    #[test]
+    fn time_bound_date_only() {
+        let bound = parse_time_bound("2024-01-10", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-10T00:00:00+00:00");
+    }
+
+    #[test]
+    fn time_bound_time_only_is_today() {
+        let bound = parse_time_bound("10:30", "utc", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-15T10:30:00+00:00");
+        // 12:00 UTC is already 2024-01-16 in Auckland (UTC+13)
+        let bound = parse_time_bound("08:00:15", "Pacific/Auckland", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2024-01-16T08:00:15+13:00");
+    }
+
+    #[test]
+    fn time_bound_invalid() {
+        assert!(parse_time_bound("yesterday-ish", "utc", now()).is_err());
+        assert!(parse_time_bound("15 parsecs", "utc", now()).is_err());
+        assert!(parse_time_bound("", "utc", now()).is_err());
+        assert!(parse_time_bound("10:30", "Invalid/Zone", now()).is_err());
+    }
+
+    #[test]
+    fn time_bound_out_of_range_amount() {
+        let err = parse_time_bound("99999999999999h", "utc", now()).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+    }
+
+    #[test]
+    fn time_bound_overflowing_subtraction() {
+        let err = parse_time_bound("1000000000d", "utc", now()).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+    }
 }
@@ -0,0 +1,287 @@
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::JlError;
+
+/// How long followed files are left idle before they are checked again when
+/// change notifications are unavailable.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Waits for changes to a set of followed files.
+///
+/// On Linux the files and their parent directories are watched with inotify,
+/// so quiet files cost nothing and rotation (a rename, delete or re-create in
+/// the directory) is noticed as soon as it happens. Elsewhere, or when inotify
+/// cannot be set up (for example when the watch limit is exhausted) or stops
+/// delivering events, it falls back to sleeping for [`POLL_INTERVAL`] and
+/// reporting every file as possibly changed.
+pub struct FileWatcher {
+    paths: Vec<PathBuf>,
+    #[cfg(target_os = "linux")]
+    inotify: Option<linux::InotifyWatch>,
+}
+
+impl FileWatcher {
+    /// Start watching the given files, falling back to polling if needed.
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            let inotify = linux::InotifyWatch::new(&paths).ok();
+            Self { paths, inotify }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Self { paths }
+        }
+    }
+
+    /// A watcher that always polls, regardless of platform support.
+    #[cfg(test)]
+    pub fn polling(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            #[cfg(target_os = "linux")]
+            inotify: None,
+        }
+    }
+
+    /// Whether this watcher falls back to timed polling.
+    #[cfg(test)]
+    pub fn is_polling(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            self.inotify.is_none()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            true
+        }
+    }
+
+    /// Block until at least one file may have changed and return the indices
+    /// of the files that should be re-checked, in ascending order.
+    pub fn wait(&mut self) -> Result<Vec<usize>, JlError> {
+        #[cfg(target_os = "linux")]
+        if let Some(ref mut inotify) = self.inotify {
+            match inotify.wait(&self.paths) {
+                Ok(changed) => return Ok(changed),
+                // Reading events failed; poll from now on rather than give up
+                Err(_) => self.inotify = None,
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+        Ok((0..self.paths.len()).collect())
+    }
+}
+
+/// The directory holding `path`, for watching renames and re-creation.
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashMap;
+    use std::ffi::OsString;
+    use std::io;
+    use std::path::PathBuf;
+
+    use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+
+    use super::parent_dir;
+
+    /// Events on a followed file itself: new data, truncation, or the file
+    /// being moved or deleted out from under us.
+    const FILE_MASK: WatchMask = WatchMask::MODIFY
+        .union(WatchMask::ATTRIB)
+        .union(WatchMask::MOVE_SELF)
+        .union(WatchMask::DELETE_SELF);
+
+    /// Events in a followed file's directory that may replace the file.
+    const DIR_MASK: WatchMask = WatchMask::CREATE
+        .union(WatchMask::MOVED_TO)
+        .union(WatchMask::MOVED_FROM)
+        .union(WatchMask::DELETE);
+
+    pub struct InotifyWatch {
+        inotify: Inotify,
+        /// Watches on the files themselves, by file index.
+        files: HashMap<WatchDescriptor, usize>,
+        /// Watches on parent directories, with the file names of interest.
+        dirs: HashMap<WatchDescriptor, Vec<(OsString, usize)>>,
+        buffer: Vec<u8>,
+    }
+
+    impl InotifyWatch {
+        pub fn new(paths: &[PathBuf]) -> io::Result<Self> {
+            let inotify = Inotify::init()?;
+            let mut files = HashMap::new();
+            let mut dirs: HashMap<WatchDescriptor, Vec<(OsString, usize)>> = HashMap::new();
+            for (index, path) in paths.iter().enumerate() {
+                // The file may not exist yet; the directory watch covers its creation
+                if let Ok(wd) = inotify.watches().add(path, FILE_MASK) {
+                    files.insert(wd, index);
+                }
+                let wd = inotify.watches().add(parent_dir(path), DIR_MASK)?;
+                if let Some(name) = path.file_name() {
+                    dirs.entry(wd).or_default().push((name.to_owned(), index));
+                }
+            }
+            Ok(Self {
+                inotify,
+                files,
+                dirs,
+                buffer: vec![0; 4096],
+            })
+        }
+
+        pub fn wait(&mut self, paths: &[PathBuf]) -> io::Result<Vec<usize>> {
+            loop {
+                let mut changed = Vec::new();
+                let mut replaced = Vec::new();
+                for event in self.inotify.read_events_blocking(&mut self.buffer)? {
+                    if event.mask.contains(EventMask::Q_OVERFLOW) {
+                        // Events were dropped; re-check everything
+                        changed.extend(0..paths.len());
+                    } else if event.mask.contains(EventMask::IGNORED) {
+                        // The watched inode is gone (deleted or unmounted)
+                        self.files.remove(&event.wd);
+                    } else if let Some(&index) = self.files.get(&event.wd) {
+                        changed.push(index);
+                    } else if let (Some(names), Some(name)) = (self.dirs.get(&event.wd), event.name)
+                    {
+                        for (_, index) in names.iter().filter(|(n, _)| n == name) {
+                            changed.push(*index);
+                            if event
+                                .mask
+                                .intersects(EventMask::CREATE | EventMask::MOVED_TO)
+                            {
+                                replaced.push(*index);
+                            }
+                        }
+                    }
+                }
+                // A new file took the path: watch the new inode instead of the
+                // rotated one, which would otherwise leak a watch and keep
+                // waking us up
+                for index in replaced {
+                    let old: Vec<WatchDescriptor> = self
+                        .files
+                        .iter()
+                        .filter(|&(_, &i)| i == index)
+                        .map(|(wd, _)| wd.clone())
+                        .collect();
+                    for wd in old {
+                        self.files.remove(&wd);
+                        // Fails if the inode is already gone, which removed the watch
+                        let _ = self.inotify.watches().remove(wd);
+                    }
+                    if let Ok(wd) = self.inotify.watches().add(&paths[index], FILE_MASK) {
+                        self.files.insert(wd, index);
+                    }
+                }
+                if !changed.is_empty() {
+                    changed.sort_unstable();
+                    changed.dedup();
+                    return Ok(changed);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn append(path: &Path, line: &str) {
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .unwrap();
+        writeln!(f, "{line}").unwrap();
+    }
+
+    #[test]
+    fn parent_dir_of_bare_name_is_current_dir() {
+        assert_eq!(parent_dir(Path::new("app.log")), Path::new("."));
+        assert_eq!(
+            parent_dir(Path::new("/var/log/app.log")),
+            Path::new("/var/log")
+        );
+    }
+
+    #[test]
+    fn polling_reports_every_file() {
+        let mut watcher = FileWatcher::polling(vec!["a.log".into(), "b.log".into()]);
+        assert!(watcher.is_polling());
+        assert_eq!(watcher.wait().unwrap(), vec![0, 1]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn inotify_reports_appended_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.log");
+        let b = dir.path().join("b.log");
+        append(&a, "a0");
+        append(&b, "b0");
+
+        let mut watcher = FileWatcher::new(vec![a.clone(), b.clone()]);
+        assert!(!watcher.is_polling());
+        append(&b, "b1");
+        assert_eq!(watcher.wait().unwrap(), vec![1]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn inotify_reports_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        append(&path, "old");
+
+        let mut watcher = FileWatcher::new(vec![path.clone()]);
+        std::fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        append(&path, "new");
+        assert_eq!(watcher.wait().unwrap(), vec![0]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn inotify_drops_rotated_file_watch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let other = dir.path().join("other.log");
+        append(&path, "old");
+        append(&other, "o0");
+
+        let mut watcher = FileWatcher::new(vec![path.clone(), other.clone()]);
+        let rotated = dir.path().join("app.log.1");
+        std::fs::rename(&path, &rotated).unwrap();
+        append(&path, "new");
+        assert_eq!(watcher.wait().unwrap(), vec![0]);
+
+        // Late writes to the archived file no longer wake the watcher
+        append(&rotated, "late");
+        append(&other, "o1");
+        assert_eq!(watcher.wait().unwrap(), vec![1]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn inotify_reports_file_created_later() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("later.log");
+
+        let mut watcher = FileWatcher::new(vec![path.clone()]);
+        assert!(!watcher.is_polling());
+        append(&path, "first");
+        assert_eq!(watcher.wait().unwrap(), vec![0]);
+    }
+}
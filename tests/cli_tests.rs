@@ -143,6 +143,413 @@ fn min_level_filters_info_lines() {
         .stdout(predicate::str::contains("error msg"));
 }
 
+// --- --where expression filter ---
+
+#[test]
+fn where_expression_filters_records() {
+    let input = concat!(
+        r#"{"@timestamp":"2024-01-15T10:30:00Z","level":"INFO","logger_name":"app","message":"fine","status":200}"#,
+        "\n",
+        r#"{"@timestamp":"2024-01-15T10:30:01Z","level":"ERROR","logger_name":"app","message":"broken","status":502}"#,
+        "\n",
+    );
+    jl().arg("--color")
+        .arg("never")
+        .arg("--where")
+        .arg("status >= 500")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("fine").not())
+        .stdout(predicate::str::contains("broken"));
+}
+
+#[test]
+fn where_invalid_expression_fails() {
+    jl().arg("--where")
+        .arg("status ==")
+        .write_stdin("{}\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid --where expression"));
+}
+
+// --- --grep message search ---
+
+#[test]
+fn grep_highlights_matches() {
+    let input = concat!(
+        r#"{"@timestamp":"2024-01-15T10:30:00Z","level":"WARN","logger_name":"app","message":"upstream timeout"}"#,
+        "\n",
+        r#"{"@timestamp":"2024-01-15T10:30:01Z","level":"INFO","logger_name":"app","message":"served"}"#,
+        "\n",
+    );
+    jl().arg("--color")
+        .arg("always")
+        .arg("--grep")
+        .arg("TIME")
+        .arg("-i")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("upstream "))
+        .stdout(predicate::str::contains(
+            "upstream \x1b[30;43mtime\x1b[0mout",
+        ))
+        .stdout(predicate::str::contains("served").not());
+}
+
+// --- -B/-A/-C context around filtered records ---
+
+#[test]
+fn before_context_shows_preceding_records() {
+    let input = concat!(
+        r#"{"level":"DEBUG","message":"step one"}"#,
+        "\n",
+        r#"{"level":"DEBUG","message":"step two"}"#,
+        "\n",
+        r#"{"level":"ERROR","message":"exploded"}"#,
+        "\n",
+        r#"{"level":"DEBUG","message":"unrelated"}"#,
+        "\n",
+    );
+    jl().arg("--color")
+        .arg("never")
+        .arg("--format")
+        .arg("{level} {message}")
+        .arg("--min-level")
+        .arg("error")
+        .arg("-B")
+        .arg("2")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout("DEBUG step one\nDEBUG step two\nERROR exploded\n");
+}
+
+// --- --since / --until time range ---
+
+#[test]
+fn since_until_filters_by_timestamp() {
+    let input = concat!(
+        r#"{"@timestamp":"2024-01-15T09:00:00Z","level":"INFO","message":"too early"}"#,
+        "\n",
+        r#"{"@timestamp":"2024-01-15T10:10:00Z","level":"INFO","message":"in range"}"#,
+        "\n",
+        r#"{"@timestamp":"2024-01-15T11:00:00Z","level":"INFO","message":"too late"}"#,
+        "\n",
+    );
+    jl().arg("--color")
+        .arg("never")
+        .arg("--tz")
+        .arg("utc")
+        .arg("--since")
+        .arg("2024-01-15T10:00")
+        .arg("--until")
+        .arg("2024-01-15T10:30")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in range"))
+        .stdout(predicate::str::contains("too early").not())
+        .stdout(predicate::str::contains("too late").not());
+}
+
+#[test]
+fn invalid_since_fails() {
+    jl().arg("--since")
+        .arg("whenever")
+        .write_stdin("{}\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid time"));
+}
+
+#[test]
+fn merge_interleaves_files_by_timestamp() {
+    let dir = tempfile::tempdir().unwrap();
+    let api = dir.path().join("api.log");
+    let db = dir.path().join("db.log");
+    std::fs::write(
+        &api,
+        concat!(
+            r#"{"@timestamp":"2024-01-15T10:00:00Z","level":"INFO","message":"request"}"#,
+            "\n",
+            r#"{"@timestamp":"2024-01-15T10:00:02Z","level":"INFO","message":"response"}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        &db,
+        concat!(
+            r#"{"@timestamp":"2024-01-15T10:00:01Z","level":"INFO","message":"query"}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    jl().args(["--merge", "--color", "never", "-f", "{message}"])
+        .arg(&api)
+        .arg(&db)
+        .assert()
+        .success()
+        .stdout("request\nquery\nresponse\n");
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    enc.write_all(data).unwrap();
+    enc.finish().unwrap()
+}
+
+#[test]
+fn gzip_file_is_decompressed() {
+    let mut tmp = NamedTempFile::new().unwrap();
+    tmp.write_all(&gzip(
+        br#"{"level":"INFO","message":"from archive"}
+"#,
+    ))
+    .unwrap();
+    tmp.flush().unwrap();
+
+    jl().args(["--color", "never", "-f", "{level} {message}"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout("INFO from archive\n");
+}
+
+#[test]
+fn gzip_stdin_is_decompressed() {
+    jl().args(["--color", "never", "-f", "{message}"])
+        .write_stdin(gzip(
+            br#"{"level":"INFO","message":"piped archive"}
+"#,
+        ))
+        .assert()
+        .success()
+        .stdout("piped archive\n");
+}
+
+#[test]
+fn directory_input_reads_rotation_set_oldest_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let line = |msg: &str| format!("{{\"level\":\"INFO\",\"message\":\"{msg}\"}}\n");
+    std::fs::write(dir.path().join("app.log"), line("current")).unwrap();
+    std::fs::write(dir.path().join("app.log.1"), line("yesterday")).unwrap();
+    std::fs::write(
+        dir.path().join("app.log.2.gz"),
+        gzip(line("two days ago").as_bytes()),
+    )
+    .unwrap();
+
+    jl().args(["--color", "never", "-f", "{message}"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout("two days ago\nyesterday\ncurrent\n");
+}
+
+#[test]
+fn docker_json_file_envelope_unwrapped() {
+    jl()
+        .args(["--color", "never", "-f", "{level} {message}"])
+        .write_stdin(
+            r#"{"log":"{\"level\":\"WARN\",\"message\":\"disk almost full\"}\n","stream":"stdout","time":"2024-01-15T10:30:00Z"}
+{"log":"plain output\n","stream":"stderr","time":"2024-01-15T10:30:01Z"}
+"#,
+        )
+        .assert()
+        .success()
+        .stdout("WARN disk almost full\nplain output\n");
+}
+
+#[test]
+fn cri_partial_lines_joined() {
+    jl().args(["--color", "never", "-f", "{level} {message}"])
+        .write_stdin(
+            r#"2024-01-15T10:30:00.000000001Z stdout P {"level":"INFO","message":"split
+2024-01-15T10:30:00.000000002Z stdout F  across chunks"}
+"#,
+        )
+        .assert()
+        .success()
+        .stdout("INFO split across chunks\n");
+}
+
+#[test]
+fn find_json_after_prefix() {
+    jl().args([
+        "--color",
+        "never",
+        "--find-json",
+        "-f",
+        "{_prefix} {level} {message}",
+    ])
+    .write_stdin(
+        r#"[pod/web/app] {"level":"INFO","message":"ready"}
+"#,
+    )
+    .assert()
+    .success()
+    .stdout("[pod/web/app] INFO ready\n");
+}
+
+#[test]
+fn multiline_pretty_printed_json() {
+    jl().args(["--color", "never", "--multiline", "-f", "{level} {message}"])
+        .write_stdin("{\n  \"level\": \"ERROR\",\n  \"message\": \"spread out\"\n}\n")
+        .assert()
+        .success()
+        .stdout("ERROR spread out\n");
+}
+
+#[test]
+fn join_continuations_attaches_stack_trace() {
+    jl()
+        .args([
+            "--color",
+            "never",
+            "--join-continuations",
+            "-f",
+            "{level} {message}",
+        ])
+        .write_stdin(
+            "{\"level\":\"ERROR\",\"message\":\"failed\"}\n\tat com.example.App.run(App.java:42)\ndone\n",
+        )
+        .assert()
+        .success()
+        .stdout("ERROR failed\n    \tat com.example.App.run(App.java:42)\ndone\n");
+}
+
+#[test]
+fn logfmt_input() {
+    jl().args([
+        "--color",
+        "never",
+        "-f",
+        "{level} {message}",
+        "--add-fields",
+        "port",
+    ])
+    .write_stdin("level=info msg=\"server started\" port=8080\n")
+    .assert()
+    .success()
+    .stdout(predicate::str::starts_with("INFO server started"))
+    .stdout(predicate::str::contains("port=8080"));
+}
+
+#[test]
+fn line_regex_input() {
+    jl().args([
+        "--color",
+        "never",
+        "-f",
+        "{level} [{logger}] {message}",
+        "--line-regex",
+        r"(?P<level>[A-Z]+) (?P<logger>\S+) - (?P<message>.*)",
+    ])
+    .write_stdin("WARN com.foo.Bar - disk low\nplain text\n")
+    .assert()
+    .success()
+    .stdout("WARN [c.f.Bar] disk low\nplain text\n");
+}
+
+#[test]
+fn access_log_line_format() {
+    jl()
+        .args([
+            "--color",
+            "never",
+            "--tz",
+            "utc",
+            "-f",
+            "{timestamp} {level} {message}",
+            "--add-fields",
+            "status",
+            "--line-format",
+            "combined",
+        ])
+        .write_stdin(
+            "10.0.0.5 - - [15/Jan/2024:10:30:00 +0100] \"GET /missing HTTP/1.1\" 404 153 \"-\" \"curl/8.5.0\"\n",
+        )
+        .assert()
+        .success()
+        .stdout("09:30:00.000 WARN GET /missing HTTP/1.1 status=404\n");
+}
+
+#[test]
+fn journald_entries() {
+    jl()
+        .args(["--color", "never", "--tz", "utc", "--add-fields", "_PID"])
+        .write_stdin(
+            r#"{"__CURSOR":"s=6f1c;i=2a","__REALTIME_TIMESTAMP":"1705314600123456","_BOOT_ID":"0d5c","PRIORITY":"4","SYSLOG_IDENTIFIER":"kernel","_PID":"1","MESSAGE":"low memory"}"#,
+        )
+        .assert()
+        .success()
+        .stdout("10:30:00.123 WARN [kernel] low memory _PID=1\n");
+}
+
+#[test]
+fn gcp_exported_entries() {
+    jl()
+        .args(["--color", "never", "--tz", "utc", "--add-fields", "order_id"])
+        .write_stdin(
+            r#"{"insertId":"1abc","logName":"projects/shop/logs/stdout","severity":"ERROR","timestamp":"2024-01-15T10:30:00Z","jsonPayload":{"message":"payment declined","order_id":42},"sourceLocation":{"file":"orders/pay.go","line":"87"}}"#,
+        )
+        .assert()
+        .success()
+        .stdout("10:30:00.000 ERROR [orders/pay.go:87] payment declined order_id=42\n");
+}
+
+#[test]
+fn ecs_nested_fields() {
+    jl()
+        .args(["--color", "never", "--tz", "utc"])
+        .write_stdin(
+            r#"{"@timestamp":"2024-01-15T10:30:00.123Z","log":{"level":"error","logger":"app.db"},"message":"connection lost","ecs":{"version":"1.6.0"}}"#,
+        )
+        .assert()
+        .success()
+        .stdout("10:30:00.123 ERROR [a.db] connection lost\n");
+}
+
+#[test]
+fn clef_message_template() {
+    jl()
+        .args(["--color", "never", "--tz", "utc", "--min-level", "info"])
+        .write_stdin(concat!(
+            r#"{"@t":"2024-01-15T10:30:00.123Z","@mt":"User {UserId} logged in","UserId":"alice","SourceContext":"Shop.Auth"}"#,
+            "\n",
+            r#"{"@t":"2024-01-15T10:30:01Z","@mt":"Cache miss for {Key}","@l":"Debug","Key":"k1"}"#,
+            "\n",
+        ))
+        .assert()
+        .success()
+        .stdout("10:30:00.123 INFO [S.Auth] User alice logged in\n");
+}
+
+#[test]
+fn go_logger_schemas() {
+    jl()
+        .args(["--color", "never", "--tz", "utc"])
+        .write_stdin(
+            r#"{"time":"2024-01-15T10:30:00.123Z","level":"INFO+3","source":{"function":"main.run","file":"/src/app/main.go","line":42},"msg":"listening"}"#,
+        )
+        .assert()
+        .success()
+        .stdout("10:30:00.123 WARN [/src/app/main.go:42] listening\n");
+
+    jl().args(["--color", "never", "--tz", "utc"])
+        .write_stdin(
+            r#"{"level":"info","ts":1705314600.123,"caller":"server/main.go:42","msg":"started"}"#,
+        )
+        .assert()
+        .success()
+        .stdout("10:30:00.123 INFO [server/main.go:42] started\n");
+}
+
 // --- --schema logrus to force schema, verify correct field extraction ---
 
 #[test]